@@ -0,0 +1,50 @@
+use criterion::{
+    black_box,
+    criterion_group,
+    criterion_main,
+    Criterion,
+};
+use parking_lot::Mutex;
+use poison_guard::Poison;
+
+fn on_unwind(c: &mut Criterion) {
+    let mut poison = Poison::new(42);
+
+    c.bench_function("on_unwind", |b| {
+        b.iter(|| {
+            let guard = Poison::on_unwind(black_box(&mut poison)).unwrap();
+
+            black_box(*guard)
+        })
+    });
+}
+
+fn on_unwind_mutex(c: &mut Criterion) {
+    let mutex = Mutex::new(Poison::new(42));
+
+    c.bench_function("on_unwind_mutex", |b| {
+        b.iter(|| {
+            let guard = Poison::on_unwind(black_box(&mutex).lock()).unwrap();
+
+            black_box(*guard)
+        })
+    });
+}
+
+fn unless_recovered(c: &mut Criterion) {
+    let mut poison = Poison::new(42);
+
+    c.bench_function("unless_recovered", |b| {
+        b.iter(|| {
+            let guard = Poison::unless_recovered(black_box(&mut poison)).unwrap();
+            let v = *guard;
+
+            Poison::recover(guard);
+
+            black_box(v)
+        })
+    });
+}
+
+criterion_group!(benches, on_unwind, on_unwind_mutex, unless_recovered);
+criterion_main!(benches);
@@ -135,6 +135,29 @@ impl Writer {
 }
 ```
 
+## Using guards in async code
+
+Guards can be held across `.await` points, and recovery guards convert into [`PoisonError`], so
+poisoned values can be propagated with `?` from an `async fn`:
+
+```
+use poison_guard::{Poison, PoisonError};
+
+# async fn fetch_changes() -> Vec<i64> { vec![] }
+async fn sync_changes(changes: &mut Poison<Vec<i64>>) -> Result<usize, PoisonError> {
+    // If the value is poisoned then `?` will return the reason why
+    let mut changes = Poison::on_unwind(changes)?;
+
+    // If the future panics while awaiting here then the value will be poisoned
+    changes.extend(fetch_changes().await);
+
+    Ok(changes.len())
+}
+```
+
+Note that a future that's dropped before it completes won't poison its guard, because dropping a
+future doesn't unwind. Use [`Poison::unless_recovered`] to poison values when a future is cancelled.
+
 ## Propagating errors and unwinds
 
 If a `Poison<T>` is poisoned, future attempts to access it may convert that into a panic or error:
@@ -184,8 +207,17 @@ The above example will output something like:
 ```text
 poisoned by a panic (the poisoning guard was acquired at 'src/lib.rs:13:38')
 ```
+
+## Unstable features
+
+On nightly compilers, building with `RUSTFLAGS="--cfg poison_guard_unstable"` implements
+`Error::provide` for [`PoisonError`]. This makes the location a value was poisoned at
+available through `std::error::request_ref::<Location>`, along with anything provided by the
+error it was poisoned with, like a backtrace.
 */
 
+#![cfg_attr(poison_guard_unstable, feature(error_generic_member_access))]
+
 mod poison;
 
 #[doc(inline)]
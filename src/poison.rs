@@ -3,16 +3,64 @@ Unwind-safe containers.
 */
 
 use std::{
+    borrow::Cow,
+    cell::Cell,
     error::Error,
+    mem,
     ops,
-    panic::{self, Location, RefUnwindSafe},
+    panic::{
+        self,
+        Location,
+    },
+    pin::Pin,
+    sync::Once,
 };
 
+#[cfg(feature = "anyhow")]
+mod anyhow_error;
+#[cfg(feature = "parking_lot")]
+mod arc;
 mod error;
 mod guard;
+mod hook;
 mod recover;
+mod stats;
+mod std_mutex;
+#[cfg(feature = "tonic")]
+mod tonic_status;
+
+pub mod local;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+pub use self::{
+    error::{
+        PoisonError,
+        PoisonRecord,
+    },
+    guard::{
+        PoisonGuard,
+        PoisonGuardPart,
+        ReleasingGuard,
+    },
+    hook::{
+        set_poison_hook,
+        take_poison_hook,
+    },
+    recover::PoisonRecover,
+    stats::PoisonStats,
+    std_mutex::{
+        StdPoisonGuard,
+        StdPoisonRecover,
+    },
+};
 
-pub use self::{error::PoisonError, guard::PoisonGuard, recover::PoisonRecover};
+#[cfg(feature = "parking_lot")]
+pub use self::arc::{
+    OwnedPoisonGuard,
+    OwnedPoisonRecover,
+};
 
 use self::error::PoisonState;
 
@@ -22,6 +70,12 @@ A container that holds a potentially poisoned value.
 `Poison<T>` doesn't manage its own synchronization, so it needs to be wrapped in a `Once` or a
 `Mutex` so it can be shared.
 
+The examples here use `parking_lot::Mutex`, which doesn't implement poisoning itself. The standard
+library's `Mutex` can also be used through [`Poison::on_unwind_std`] and
+[`Poison::unless_recovered_std`], which ignore the `Mutex`'s own poisoning in favor of `Poison<T>`'s.
+Async locks like `futures::lock::Mutex` work the same way as `parking_lot::Mutex`; pass the guard
+returned by `.lock().await` to [`Poison::on_unwind`] or [`Poison::unless_recovered`].
+
 ## Protecting state
 
 Wrapping some state in a `Poison<T>` requires specific guard types to access. These guards
@@ -54,7 +108,7 @@ They're both static rather than inherent:
 
 - [`Poison::on_unwind`] for guards that only poison if a panic unwinds through them.
 - [`Poison::unless_recovered`] for guards that remain poisoned unless they're explicitly recovered
-after operating on their state. These also protect against early returns from `?`.
+  after operating on their state. These also protect against early returns from `?`.
 
 ## Recovering state
 
@@ -103,14 +157,31 @@ fn with_state(state: Arc<Mutex<Poison<Vec<i32>>>>) {
     guard.push(42);
 }
 ```
+
+## Layout
+
+`Poison<T>` is `#[repr(C)]`, with the inner value as its first field. That means a pointer to a
+`Poison<T>` is also a valid pointer to its `T`, and the layout of a `Poison<T>` embedded in other
+`#[repr(C)]` types is stable. The layout of the poison state that follows the value is private
+and may change. Aliasing the `T` bypasses poisoning entirely, so it should only be done by code
+that doesn't need to know whether the value is valid.
+
+## Auto traits
+
+`Poison<T>` is `Send`, `Sync`, `Unpin`, `UnwindSafe`, and `RefUnwindSafe` when `T` is. The poison
+state itself doesn't affect any of these traits. Guards are always `UnwindSafe`, because they
+poison the value if a panic unwinds through them.
+
+`Poison<T>` isn't `RefUnwindSafe` for every `T`, because a shared reference can still reach
+interior mutability in `T` without acquiring a guard. A panic while mutating a `RefCell<T>`
+through [`Poison::get`] could leave the value broken without poisoning it.
 */
+#[repr(C)]
 pub struct Poison<T> {
     value: T,
     state: PoisonState,
 }
 
-impl<T> RefUnwindSafe for Poison<T> {}
-
 impl<T> Poison<T> {
     /**
     Create a new `Poison<T>` with a valid inner value.
@@ -142,6 +213,58 @@ impl<T> Poison<T> {
         }
     }
 
+    /**
+    Create a new `Poison<T>` with a valid inner value that tracks statistics about how often
+    it's poisoned.
+
+    Statistics are opt-in because they're shared through an atomic, which needs to be allocated
+    up-front. See [`Poison::poisoned_count`] and [`Poison::stats`] for reading them.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::with_stats(42);
+
+    drop(Poison::unless_recovered(&mut v).unwrap());
+
+    assert_eq!(1, v.poisoned_count());
+    ```
+    */
+    pub fn with_stats(v: T) -> Self {
+        Poison {
+            value: v,
+            state: PoisonState::with_stats(),
+        }
+    }
+
+    /**
+    Create a new `Poison<T>` that's poisoned with the given error.
+
+    This can be used with [`PoisonError::from_record`] to restore a value that was poisoned
+    before a process restarted.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonError, PoisonRecord};
+
+    let record = PoisonRecord::new(true, Some("explicit panic".into()), "src/main.rs", 13, 38);
+
+    let v = Poison::new_poisoned(PoisonError::from_record(record), 42);
+
+    assert!(v.is_poisoned_by_panic());
+    ```
+    */
+    #[track_caller]
+    pub fn new_poisoned(err: PoisonError, v: T) -> Self {
+        Poison {
+            value: v,
+            state: PoisonState::from_error(err),
+        }
+    }
+
     /**
     Try create a new `Poison<T>` with an initialization function that may unwind.
 
@@ -195,6 +318,122 @@ impl<T> Poison<T> {
         }
     }
 
+    /**
+    Try create a new `Poison<T>` with an initialization function that may unwind, using a
+    sentinel value if it does.
+
+    This method is like [`Poison::new_catch_unwind`], but doesn't require `T: Default`. If
+    initialization unwinds then the `Poison<T>` is poisoned and holds `sentinel` instead. If it
+    doesn't unwind then `sentinel` is dropped.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    struct Port(u16);
+
+    let port = Poison::new_catch_unwind_or(Port(0), || {
+        let port = "eighty".parse().expect("invalid port");
+
+        Port(port)
+    });
+
+    assert!(port.is_poisoned());
+    assert_eq!(0, port.peek().0);
+    ```
+    */
+    #[track_caller]
+    pub fn new_catch_unwind_or(sentinel: T, f: impl FnOnce() -> T) -> Self {
+        match panic::catch_unwind(panic::AssertUnwindSafe(f)) {
+            Ok(v) => Poison {
+                value: v,
+                state: PoisonState::from_unpoisoned(),
+            },
+            Err(panic) => Poison {
+                value: sentinel,
+                state: PoisonState::from_panic(Location::caller(), Some(panic)),
+            },
+        }
+    }
+
+    /**
+    Try create a new `Poison<T>` with an initialization function that may unwind, capturing the
+    formatted panic message through a panic hook.
+
+    [`Poison::new_catch_unwind`] can only capture panic messages when the payload is a string.
+    This method uses a panic hook so that panics with other payloads, like those raised through
+    [`std::panic::panic_any`], still produce a message. Panics with string payloads are captured
+    the same way as through [`Poison::new_catch_unwind`].
+
+    **This method installs a process-wide panic hook the first time it's called.** The hook
+    wraps whatever hook was set at the time, and forwards any panics that aren't raised by `f`
+    to it. The hook is never removed, so panic hooks set afterwards through
+    [`std::panic::set_hook`] will replace it, and panic messages from other payloads won't be
+    captured anymore. Installing the hook will panic if the current thread is already panicking,
+    like when it's called from a `Drop` implementation while unwinding.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+    use std::panic;
+
+    let v: Poison<i32> = Poison::new_catch_unwind_with_hook(|| panic::panic_any(42));
+
+    assert!(v.get().unwrap_err().to_string().contains("panicked at"));
+    ```
+    */
+    #[track_caller]
+    pub fn new_catch_unwind_with_hook(f: impl FnOnce() -> T) -> Self
+    where
+        T: Default,
+    {
+        thread_local! {
+            static CAPTURED: Cell<Option<Option<String>>> = const { Cell::new(None) };
+        }
+
+        static INSTALL_HOOK: Once = Once::new();
+
+        // Only capture panics on threads that are running `f`, forwarding any others to the
+        // hook that was set before
+        INSTALL_HOOK.call_once(|| {
+            let previous = panic::take_hook();
+
+            panic::set_hook(Box::new(move |info| {
+                if CAPTURED.with(|captured| captured.take()).is_some() {
+                    CAPTURED.with(|captured| captured.set(Some(Some(info.to_string()))));
+                } else {
+                    previous(info)
+                }
+            }));
+        });
+
+        let outer = CAPTURED.with(|captured| captured.replace(Some(None)));
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+        let captured = CAPTURED.with(|captured| captured.replace(outer)).flatten();
+
+        match result {
+            Ok(v) => Poison {
+                value: v,
+                state: PoisonState::from_unpoisoned(),
+            },
+            Err(panic) => {
+                let panic = match captured {
+                    Some(msg) if !panic.is::<&'static str>() && !panic.is::<String>() => {
+                        Box::new(msg)
+                    }
+                    _ => panic,
+                };
+
+                Poison {
+                    value: Default::default(),
+                    state: PoisonState::from_panic(Location::caller(), Some(panic)),
+                }
+            }
+        }
+    }
+
     /**
     Try create a new `Poison<T>` with an initialization function that may fail or unwind.
 
@@ -260,220 +499,1081 @@ impl<T> Poison<T> {
     If this method returns `true` then [`Poison::get`], [`Poison::on_unwind`], [`Poison::unless_recovered`]
     etc will return `Ok`. Otherwise these methods will return `Err` with a recovery guard.
     */
+    #[inline]
     pub fn is_poisoned(&self) -> bool {
         self.state.is_poisoned()
     }
 
     /**
-    Try get the inner value.
+    Whether the state has allocated a payload, used to check guards don't allocate.
+    */
+    #[cfg(test)]
+    pub(crate) fn is_allocated(&self) -> bool {
+        self.state.is_allocated()
+    }
 
-    This will return `Err` if the value is poisoned. The recovery guard returned in the poisoned
-    case can be converted into a standard error type.
+    /**
+    Whether or not the value was poisoned by a panic.
 
-    ## Examples
+    This is a cheaper alternative to checking [`PoisonError::is_recoverable`] on the error
+    returned by [`Poison::get`].
+    */
+    pub fn is_poisoned_by_panic(&self) -> bool {
+        self.state.is_poisoned_by_panic()
+    }
 
-    Get the value of a potentially poisoned global value:
+    /**
+    Whether or not the value was poisoned by something other than a panic.
+
+    This includes values poisoned by an error, or by a guard that was released without being
+    recovered.
+    */
+    pub fn is_poisoned_by_error(&self) -> bool {
+        self.state.is_poisoned() && !self.state.is_poisoned_by_panic()
+    }
+
+    /**
+    Freeze the value, so that no more guards can be acquired for it.
+
+    A frozen value can still be read through methods like [`Poison::get`], but attempting to
+    acquire a guard through [`Poison::on_unwind`] or [`Poison::unless_recovered`] will return
+    `Err` with a guard that reports [`PoisonRecover::is_frozen`]. Frozen values can't be recovered,
+    so [`PoisonRecover::try_recover_with`] will return `Err` and methods like
+    [`PoisonRecover::recover`] will panic. Frozen values can't be poisoned either, so methods like
+    [`Poison::poison_with`] will return `Err`. These errors will report
+    [`PoisonError::is_frozen`].
+
+    This method will return `Err` if the value is already poisoned.
+
+    ## Examples
 
     ```
-    use once_cell::sync::Lazy;
     use poison_guard::Poison;
 
-    # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    static SHARED: Lazy<Poison<i32>> = Lazy::new(|| Poison::new(42));
+    let mut config = Poison::new(vec![("port", 8080)]);
 
-    let value = SHARED.get()?;
+    // Validate the config, and then freeze it
+    config.freeze().unwrap();
 
-    assert_eq!(42, *value);
-    # Ok(())
-    # }
+    assert_eq!(8080, config.get().unwrap()[0].1);
+
+    // No more guards can be acquired for the value
+    let recover = Poison::on_unwind(&mut config).unwrap_err();
+
+    assert!(recover.is_frozen());
     ```
     */
-    pub fn get(&self) -> Result<&T, PoisonRecover<T, &Self>> {
-        if self.is_poisoned() {
-            Err(PoisonRecover::recover_to_poison_on_unwind(self))
-        } else {
-            Ok(&self.value)
-        }
+    pub fn freeze(&mut self) -> Result<(), PoisonError> {
+        self.state.freeze()
     }
 
     /**
-    Get a guard to the value that will only poison if a panic unwinds through the guard.
+    Whether or not the value is frozen.
 
-    When the guard is dropped the value will be unpoisoned, unless a panic unwound through it.
-    If the guard is forgotten through `mem::forget` then the value will also be poisoned.
+    See [`Poison::freeze`] for more details.
+    */
+    pub fn is_frozen(&self) -> bool {
+        self.state.is_frozen()
+    }
 
-    See [`Poison::unless_recovered`] for an alternative to this method that also poisons on
-    other early returns, like `?`.
+    /**
+    Get the number of times the value has been poisoned.
 
-    ## Examples
+    The generation is incremented each time the value transitions from a valid state to a
+    poisoned one. Recovering a value doesn't reset its generation, so a caller can take a snapshot
+    of the generation and compare it later to see whether the value was poisoned in the meantime,
+    even if it has since been recovered.
 
-    Guarding a local variable or field:
+    Guards acquired through [`Poison::unless_recovered`] only advance the generation if they're
+    released without being recovered. Guards acquired through [`Poison::on_unwind`] only advance
+    it if a panic unwinds through them.
+
+    ## Examples
 
     ```
     use poison_guard::Poison;
 
     let mut v = Poison::new(42);
 
-    let guard = Poison::on_unwind(&mut v).unwrap();
-
-    assert_eq!(42, *guard);
-    ```
+    let generation = v.poison_generation();
 
-    Guarding a mutex:
-
-    ```
-    use poison_guard::Poison;
-    use parking_lot::Mutex;
+    // Recovering a guard doesn't advance the generation
+    Poison::recover(Poison::unless_recovered(&mut v).unwrap());
 
-    // This type is semantically equivalent to the standard library's `Mutex`.
-    // `parking_lot` doesn't implement poisoning itself, it will simply unlock
-    // if a panic unwinds through a guard.
-    let mutex = Mutex::new(Poison::new(42));
+    assert_eq!(generation, v.poison_generation());
 
-    let guard = Poison::on_unwind(mutex.lock()).unwrap();
+    // Dropping a guard without recovering it poisons the value and advances the generation
+    drop(Poison::unless_recovered(&mut v).unwrap());
+    drop(Poison::on_unwind(&mut v).unwrap_err().recover());
 
-    assert_eq!(42, *guard);
+    assert!(!v.is_poisoned());
+    assert_eq!(generation + 1, v.poison_generation());
     ```
     */
-    #[track_caller]
-    pub fn on_unwind<'a, Target>(
-        poison: Target,
-    ) -> Result<PoisonGuard<'a, T, Target>, PoisonRecover<'a, T, Target>>
-    where
-        Target: ops::DerefMut<Target = Poison<T>> + 'a,
-    {
-        if poison.is_poisoned() {
-            Err(PoisonRecover::recover_to_poison_on_unwind(poison))
-        } else {
-            Ok(PoisonGuard::poison_on_unwind(poison))
-        }
+    pub fn poison_generation(&self) -> u64 {
+        self.state.generation()
     }
 
     /**
-    Get a guard to the value that will immediately poison and only unpoison with [`Poison::recover`] or [`Poison::try_recover`].
+    Get the reason the value was last poisoned, if it's since been recovered.
 
-    This method is an alternative to [`Poison::on_unwind`] that can also protect state against early
-    returns through non-panicking control flow, like `?`.
+    The reason is kept after the value is recovered, so it can be used for auditing values that
+    were previously poisoned. It's replaced whenever the value is poisoned and recovered again.
+    If the value is currently poisoned then the reason is available through [`Poison::get`]
+    instead.
 
     ## Examples
 
-    Guarding a local variable or field:
-
     ```
-    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
-    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     use poison_guard::Poison;
 
     let mut v = Poison::new(42);
 
-    let mut guard = Poison::unless_recovered(&mut v)?;
+    assert!(v.last_poison().is_none());
 
-    // If this call fails the value will remain poisoned
-    some_fallible_operation(&mut guard)?;
+    v.poison_with("the value is no longer valid").unwrap();
 
-    // If we get this far then the state is still valid, so return the guard and unpoison
-    Poison::recover(guard);
-    # Ok(())
-    # }
+    // Recover the value
+    drop(Poison::on_unwind(&mut v).unwrap_err().recover());
+
+    assert_eq!(
+        "the value is no longer valid",
+        v.last_poison().unwrap().message().unwrap(),
+    );
     ```
+    */
+    pub fn last_poison(&self) -> Option<&PoisonError> {
+        self.state.last_cleared()
+    }
 
-    Poisoning a mutex:
+    /**
+    Get the number of times the value has been poisoned over its lifetime.
+
+    This is only tracked for values created with [`Poison::with_stats`], and is always `0`
+    for other values. Unlike [`Poison::poison_generation`], the count can also be read through
+    a [`PoisonStats`] handle without access to the value itself, and guards acquired through
+    [`Poison::unless_recovered`] are only counted if they're released without being recovered.
+    */
+    pub fn poisoned_count(&self) -> u64 {
+        self.state.stats().map_or(0, PoisonStats::poisoned_count)
+    }
+
+    /**
+    Get a handle to the statistics for the value.
+
+    This will return `None` if the value wasn't created with [`Poison::with_stats`]. The handle
+    can be read from other threads while the value is locked.
+
+    ## Examples
 
     ```
-    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
-    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     use poison_guard::Poison;
     use parking_lot::Mutex;
+    use std::sync::Arc;
 
-    let mutex = Mutex::new(Poison::new(42));
+    let shared = Arc::new(Mutex::new(Poison::with_stats(42)));
 
-    let mut guard = Poison::unless_recovered(mutex.lock())?;
+    let stats = shared.lock().stats().unwrap();
 
-    // If this call fails the value will remain poisoned
-    some_fallible_operation(&mut guard)?;
+    let _guard = Poison::on_unwind(shared.lock()).unwrap();
 
-    // If we get this far then the state is still valid, so return the guard and unpoison
-    Poison::recover(guard);
-    # Ok(())
-    # }
+    // The stats can be read while the value is locked
+    assert_eq!(0, stats.poisoned_count());
     ```
     */
-    #[track_caller]
-    pub fn unless_recovered<'a, Target>(
-        poison: Target,
-    ) -> Result<PoisonGuard<'a, T, Target>, PoisonRecover<'a, T, Target>>
-    where
-        Target: ops::DerefMut<Target = Poison<T>> + 'a,
-    {
-        if poison.is_poisoned() {
-            Err(PoisonRecover::recover_to_poison_now(poison))
-        } else {
-            Ok(PoisonGuard::poison_now(poison))
-        }
+    pub fn stats(&self) -> Option<PoisonStats> {
+        self.state.stats().cloned()
     }
 
     /**
-    Recover a guard, unpoisoning it if it was poisoned.
+    Register a callback that's called whenever this value becomes poisoned.
 
-    This method must be used to recover guards acquired through [`Poison::unless_recovered`].
-    Guards acquired through [`Poison::on_unwind`] can also be returned this way, but it isn't
-    necessary to do so.
+    The callback is called on the thread that poisoned the value, at the moment it's poisoned,
+    which may be in the middle of unwinding from a panic. If the callback itself panics while
+    the thread is unwinding then the process will abort.
 
-    # Examples
+    See [`set_poison_hook`](crate::set_poison_hook) for a callback that's called when any value
+    becomes poisoned.
 
-    Guarding a local variable or field:
+    ## Examples
 
     ```
-    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
-    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     use poison_guard::Poison;
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel();
 
     let mut v = Poison::new(42);
 
-    let mut guard = Poison::unless_recovered(&mut v)?;
+    v.on_poison(move |err| {
+        let _ = tx.send(err.to_string());
+    });
 
-    // If this call fails the value will remain poisoned
-    some_fallible_operation(&mut guard)?;
+    // Dropping this guard without recovering it will call the callback
+    drop(Poison::unless_recovered(&mut v).unwrap());
 
-    // If we get this far then the state is still valid, so return the guard and unpoison
-    Poison::recover(guard);
-    # Ok(())
-    # }
+    assert!(rx.try_recv().is_ok());
     ```
     */
-    pub fn recover<'a, Target>(guard: PoisonGuard<'a, T, Target>)
-    where
-        Target: ops::DerefMut<Target = Poison<T>> + 'a,
-    {
-        PoisonGuard::unpoison_now(guard);
+    pub fn on_poison(&mut self, f: impl Fn(&PoisonError) + Send + Sync + 'static) {
+        self.state.observe(Box::new(f));
     }
 
     /**
-    Try recover a guard based on a result.
+    Poison the value with the given error.
 
-    This method can be used to wrap a call that operates on the underlying value. If the operation
-    fails then the value will be poisoned. This approach differs from [`Poison::recover`] by
-    capturing the error and storing it in the `Poison<T>`. Future attempts to access the value will
-    include the original error.
+    This method can be used to poison a value based on some external signal, without needing
+    to acquire a guard first. Any attempt to access the poisoned value will instead return this
+    error unless the `Poison<T>` is recovered.
 
-    ## Examples
+    This method will return `Err` if the value is frozen, because frozen values can't be
+    poisoned. The error will report [`PoisonError::is_frozen`].
 
-    Guarding a local variable or field:
+    ## Examples
 
     ```
-    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
-    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     use poison_guard::Poison;
 
     let mut v = Poison::new(42);
 
-    let mut guard = Poison::unless_recovered(&mut v)?;
+    v.poison_with("the value is no longer valid").unwrap();
 
-    // If this call fails the value will remain poisoned
-    // The error will be captured and a wrapper will be returned
-    Poison::try_recover(some_fallible_operation(&mut guard), guard)?;
-    # Ok(())
-    # }
+    assert!(v.is_poisoned());
+    ```
+    */
+    #[track_caller]
+    pub fn poison_with<E>(&mut self, e: E) -> Result<(), PoisonError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        if self.is_frozen() {
+            return Err(self.state.to_error());
+        }
+
+        self.state
+            .poison_with_error_at(Location::caller(), Some(e.into()));
+
+        Ok(())
+    }
+
+    /**
+    Poison the value with the given error and a code categorizing it.
+
+    The code can be read back through [`PoisonError::code`]. Like [`Poison::poison_with`], this
+    method will return `Err` if the value is frozen.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonError};
+
+    const CORRUPT_INDEX: u32 = 3;
+
+    let mut v = Poison::new(42);
+
+    v.poison_with_code("the index is corrupt", CORRUPT_INDEX).unwrap();
+
+    let err = PoisonError::from(v.get().unwrap_err());
+
+    assert_eq!(Some(CORRUPT_INDEX), err.code());
+    ```
+    */
+    #[track_caller]
+    pub fn poison_with_code<E>(&mut self, e: E, code: u32) -> Result<(), PoisonError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        if self.is_frozen() {
+            return Err(self.state.to_error());
+        }
+
+        self.state
+            .poison_with_error_code_at(Location::caller(), e.into(), code);
+
+        Ok(())
+    }
+
+    /**
+    Transform the reason the value was poisoned.
+
+    If the value is poisoned then the function is called with its error and the result replaces
+    it. This can be used to add context to a poisoned value at a module boundary. If the value
+    isn't poisoned then the function isn't called. Transforming the error doesn't count as a new
+    poisoning, so observers and hooks aren't notified. [`PoisonError::context`] adds context while
+    keeping the original error.
+
+    If the function returns an error that doesn't represent a poisoned value, like the error for
+    a frozen value, then the value remains poisoned with an unknown error.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonError};
+
+    let mut v = Poison::new(42);
+    v.poison_with("invalid checksum").unwrap();
+
+    v.map_poison(|err| err.context("while loading segment 5"));
+
+    let err = PoisonError::from(v.get().unwrap_err());
+
+    assert_eq!(Some("invalid checksum"), err.message().as_deref());
+    assert_eq!("while loading segment 5: poisoned by an error", format!("{:#}", err));
+    ```
+    */
+    #[track_caller]
+    pub fn map_poison(&mut self, f: impl FnOnce(PoisonError) -> PoisonError) {
+        self.state.map_error(f);
+    }
+
+    /**
+    Replace the value with the result of a function that consumes it.
+
+    If the function panics then the panic is caught and the value is poisoned with it. The
+    original value was moved into the function, so it's replaced with its default.
+
+    This method will return `Err` without calling the function if the value is poisoned or
+    frozen.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut v = Poison::new(vec![3, 1, 2]);
+
+    v.update(|mut v| {
+        v.sort();
+        v
+    })?;
+
+    assert_eq!(&[1, 2, 3], &**v.get()?);
+    # Ok(())
+    # }
+    ```
+    */
+    #[track_caller]
+    pub fn update(&mut self, f: impl FnOnce(T) -> T) -> Result<(), PoisonError>
+    where
+        T: Default,
+    {
+        if self.is_poisoned() || self.is_frozen() {
+            return Err(self.state.to_error());
+        }
+
+        let value = mem::take(&mut self.value);
+
+        match panic::catch_unwind(panic::AssertUnwindSafe(move || f(value))) {
+            Ok(value) => {
+                self.value = value;
+
+                Ok(())
+            }
+            Err(panic) => {
+                self.state.poison_with_panic(Some(panic));
+
+                Err(self.state.to_error())
+            }
+        }
+    }
+
+    /**
+    Take the value, leaving its default in its place.
+
+    This is like `mem::take`, but will return `Err` without taking the value if it's poisoned
+    or frozen, so a poisoned value isn't silently replaced with a valid one.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut batch = Poison::new(Vec::new());
+
+    Poison::on_unwind(&mut batch).unwrap().push(1);
+    Poison::on_unwind(&mut batch).unwrap().push(2);
+
+    assert_eq!(vec![1, 2], batch.take()?);
+    assert!(batch.get()?.is_empty());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn take(&mut self) -> Result<T, PoisonError>
+    where
+        T: Default,
+    {
+        if self.is_poisoned() || self.is_frozen() {
+            return Err(self.state.to_error());
+        }
+
+        Ok(mem::take(&mut self.value))
+    }
+
+    /**
+    Swap the value and poison state with another `Poison<T>`.
+
+    This is equivalent to calling `mem::swap` on the two `Poison<T>`s, so a poisoned value will
+    stay poisoned after it's been swapped.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut front = Poison::new(vec![1, 2, 3]);
+    let mut back = Poison::new(vec![]);
+
+    front.swap(&mut back);
+
+    assert!(front.get().unwrap().is_empty());
+    assert_eq!(3, back.get().unwrap().len());
+    ```
+    */
+    pub fn swap(&mut self, other: &mut Poison<T>) {
+        mem::swap(self, other);
+    }
+
+    /**
+    Try get the inner value.
+
+    This will return `Err` if the value is poisoned. The recovery guard returned in the poisoned
+    case can be converted into a standard error type.
+
+    ## Examples
+
+    Get the value of a potentially poisoned global value:
+
+    ```
+    use once_cell::sync::Lazy;
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    static SHARED: Lazy<Poison<i32>> = Lazy::new(|| Poison::new(42));
+
+    let value = SHARED.get()?;
+
+    assert_eq!(42, *value);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn get(&self) -> Result<&T, PoisonRecover<'_, T, &Self>> {
+        if self.is_poisoned() {
+            Err(PoisonRecover::recover_to_poison_on_unwind(self))
+        } else {
+            Ok(&self.value)
+        }
+    }
+
+    /**
+    Try clone the inner value into a new, independent `Poison<T>`.
+
+    This will return `Err` if the value is poisoned. The clone is always unpoisoned, and doesn't
+    share any poison state with the original, like its generation or observers.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let v = Poison::new(vec![1, 2, 3]);
+
+    let snapshot = v.try_clone()?;
+
+    assert_eq!(&[1, 2, 3], &**snapshot.get()?);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn try_clone(&self) -> Result<Poison<T>, PoisonError>
+    where
+        T: Clone,
+    {
+        if self.is_poisoned() {
+            Err(self.state.to_error())
+        } else {
+            Ok(Poison::new(self.value.clone()))
+        }
+    }
+
+    /**
+    Try get a clone of the inner value.
+
+    This will return `Err` if the value is poisoned. It's useful for taking a snapshot of a value
+    behind a lock, so the lock can be released straight away.
+
+    ## Examples
+
+    ```
+    use parking_lot::Mutex;
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let shared = Mutex::new(Poison::new(vec![1, 2, 3]));
+
+    let snapshot = shared.lock().get_cloned()?;
+
+    assert_eq!(vec![1, 2, 3], snapshot);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn get_cloned(&self) -> Result<T, PoisonError>
+    where
+        T: Clone,
+    {
+        if self.is_poisoned() {
+            Err(self.state.to_error())
+        } else {
+            Ok(self.value.clone())
+        }
+    }
+
+    /**
+    Get the inner value, recovering it first with the given closure if it's poisoned.
+
+    If the value isn't poisoned then the closure won't be called.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    // Poison the value by dropping a guard without recovering it
+    drop(Poison::unless_recovered(&mut v).unwrap());
+
+    let v = v.get_or_recover_with(|v| v.clear());
+
+    assert!(v.is_empty());
+    ```
+    */
+    pub fn get_or_recover_with(&mut self, f: impl FnOnce(&mut T)) -> &T {
+        if self.is_poisoned() {
+            f(&mut self.value);
+            self.state.unpoison();
+        }
+
+        &self.value
+    }
+
+    /**
+    Recover the value with the given closure if the reason it was poisoned matches a predicate.
+
+    If the value is poisoned and `predicate` returns `true` for the reason it was poisoned then
+    `recover` is called and the value is unpoisoned. If `predicate` returns `false` then the
+    value is left poisoned and its error is returned. If the value isn't poisoned then neither
+    closure is called.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    const RETRYABLE: u32 = 1;
+
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    v.poison_with_code("the connection was reset", RETRYABLE).unwrap();
+
+    v.recover_if(|err| err.code() == Some(RETRYABLE), |v| v.clear()).unwrap();
+
+    assert!(v.get().unwrap().is_empty());
+    ```
+    */
+    pub fn recover_if(
+        &mut self,
+        predicate: impl FnOnce(&PoisonError) -> bool,
+        recover: impl FnOnce(&mut T),
+    ) -> Result<(), PoisonError> {
+        if !self.is_poisoned() {
+            return Ok(());
+        }
+
+        let err = self.state.to_error();
+
+        if predicate(&err) {
+            recover(&mut self.value);
+            self.state.unpoison();
+
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /**
+    Try get the inner value, returning `None` if it's poisoned.
+
+    This is a lighter alternative to [`Poison::get`] for callers that don't need to
+    recover or report on a poisoned value.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let v = Poison::new(42);
+
+    if let Some(v) = v.try_deref() {
+        assert_eq!(42, *v);
+    }
+    ```
+    */
+    pub fn try_deref(&self) -> Option<&T> {
+        if self.is_poisoned() {
+            None
+        } else {
+            Some(&self.value)
+        }
+    }
+
+    /**
+    Try iterate over the inner collection.
+
+    This will return `Err` if the value is poisoned.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let v = Poison::new(vec![1, 2, 3]);
+
+    let sum: i32 = v.iter()?.sum();
+
+    assert_eq!(6, sum);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn iter<'a>(&'a self) -> Result<<&'a T as IntoIterator>::IntoIter, PoisonError>
+    where
+        &'a T: IntoIterator,
+    {
+        if self.is_poisoned() {
+            Err(self.state.to_error())
+        } else {
+            Ok(self.value.into_iter())
+        }
+    }
+
+    /**
+    Get the inner value, regardless of whether it's poisoned.
+
+    The value may observe inconsistent state if it's poisoned. This method is an escape hatch
+    for diagnostics, like dumping a poisoned value into a crash report. Use [`Poison::get`] to
+    only access the value when it's valid.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    let mut guard = Poison::unless_recovered(&mut v).unwrap();
+    guard.push(4);
+    drop(guard);
+
+    assert!(v.is_poisoned());
+
+    // The value can still be inspected, even though it's poisoned
+    assert_eq!(&[1, 2, 3, 4], &**v.peek());
+    ```
+    */
+    pub fn peek(&self) -> &T {
+        &self.value
+    }
+
+    /**
+    Take the value and the reason it was poisoned out of a poisoned `Poison<T>`.
+
+    This can be used to salvage a value that can't be recovered, while still keeping the
+    reason it was poisoned around for reporting. If the value isn't poisoned then the
+    `Poison<T>` is returned unchanged, so the value isn't lost.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    let mut guard = Poison::unless_recovered(&mut v).unwrap();
+    guard.push(4);
+    drop(guard);
+
+    let (partial, err) = v.into_poisoned_parts().ok().unwrap();
+
+    eprintln!("salvaging {:?} after failure: {}", partial, err);
+
+    assert_eq!(vec![1, 2, 3, 4], partial);
+    ```
+    */
+    pub fn into_poisoned_parts(self) -> Result<(T, PoisonError), Self> {
+        if self.is_poisoned() {
+            let err = self.state.to_error();
+
+            Ok((self.value, err))
+        } else {
+            Err(self)
+        }
+    }
+
+    /**
+    Get a guard to the value that will only poison if a panic unwinds through the guard.
+
+    When the guard is dropped the value will be unpoisoned, unless a panic unwound through it.
+    If the guard is forgotten through `mem::forget` then the value will also be poisoned.
+
+    See [`Poison::unless_recovered`] for an alternative to this method that also poisons on
+    other early returns, like `?`.
+
+    ## Examples
+
+    Guarding a local variable or field:
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(42);
+
+    let guard = Poison::on_unwind(&mut v).unwrap();
+
+    assert_eq!(42, *guard);
+    ```
+
+    Guarding a mutex:
+
+    ```
+    use poison_guard::Poison;
+    use parking_lot::Mutex;
+
+    // This type is semantically equivalent to the standard library's `Mutex`.
+    // `parking_lot` doesn't implement poisoning itself, it will simply unlock
+    // if a panic unwinds through a guard.
+    let mutex = Mutex::new(Poison::new(42));
+
+    let guard = Poison::on_unwind(mutex.lock()).unwrap();
+
+    assert_eq!(42, *guard);
+    ```
+    */
+    #[track_caller]
+    pub fn on_unwind<'a, Target>(
+        poison: Target,
+    ) -> Result<PoisonGuard<'a, T, Target>, PoisonRecover<'a, T, Target>>
+    where
+        Target: ops::DerefMut<Target = Poison<T>> + 'a,
+    {
+        if poison.is_poisoned() || poison.is_frozen() {
+            Err(PoisonRecover::recover_to_poison_on_unwind(poison))
+        } else {
+            Ok(PoisonGuard::poison_on_unwind(poison))
+        }
+    }
+
+    /**
+    Get a pinned guard to a pinned value that will only poison if a panic unwinds through the
+    guard.
+
+    This method is like [`Poison::on_unwind`], but for values that are `!Unpin`, like futures.
+    Pinning is projected through the guard, so [`Pin::as_mut`] on the returned guard gives a
+    `Pin<&mut T>`. If the value is poisoned or frozen then its error is returned instead of a
+    recovery guard, because recovering a value can move it.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+    use std::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+
+    let mut poison = pin!(Poison::new(async { 42 }));
+
+    let mut guard = Poison::on_unwind_pinned(poison.as_mut()).unwrap();
+
+    let mut cx = Context::from_waker(Waker::noop());
+
+    assert_eq!(Poll::Ready(42), guard.as_mut().poll(&mut cx));
+    ```
+    */
+    #[track_caller]
+    pub fn on_unwind_pinned<'a>(
+        poison: Pin<&'a mut Poison<T>>,
+    ) -> Result<Pin<PoisonGuard<'a, T>>, PoisonError> {
+        // SAFETY: The value is never moved out of the `Poison<T>` here, and the guard is
+        // pinned before it's returned, so it can only give out `Pin<&mut T>`
+        let poison = unsafe { poison.get_unchecked_mut() };
+
+        if poison.is_poisoned() || poison.is_frozen() {
+            return Err(poison.state.to_error());
+        }
+
+        // SAFETY: The guard derefs to the pinned value and never moves it
+        Ok(unsafe { Pin::new_unchecked(PoisonGuard::poison_on_unwind(poison)) })
+    }
+
+    /**
+    Get a guard to the value that will only poison if a panic unwinds through the guard, and
+    won't poison if the guard is forgotten.
+
+    Guards acquired through [`Poison::on_unwind`] poison the value if they're passed to
+    `mem::forget`, because there's no way to tell whether the critical section completed.
+    This method can be used for FFI patterns that intentionally forget guards after handing
+    ownership of the value elsewhere.
+
+    **Forgetting the guard leaves the value unpoisoned, even if it was left in an invalid state.**
+    Poisoning relies entirely on the guard's destructor, so a guard that's forgotten in the
+    middle of changing the value, such as by a leaked `Rc` cycle, will go undetected. If the
+    value is poisoned then recovering it will return a guard that does poison on forget.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+    use std::mem;
+
+    let mut v = Poison::new(42);
+
+    let guard = Poison::on_unwind_no_forget_poison(&mut v).unwrap();
+
+    mem::forget(guard);
+
+    assert!(!v.is_poisoned());
+    ```
+    */
+    #[track_caller]
+    pub fn on_unwind_no_forget_poison<'a, Target>(
+        poison: Target,
+    ) -> Result<PoisonGuard<'a, T, Target>, PoisonRecover<'a, T, Target>>
+    where
+        Target: ops::DerefMut<Target = Poison<T>> + 'a,
+    {
+        if poison.is_poisoned() || poison.is_frozen() {
+            Err(PoisonRecover::recover_to_poison_on_unwind(poison))
+        } else {
+            Ok(PoisonGuard::poison_on_unwind_unless_forgotten(poison))
+        }
+    }
+
+    /**
+    Get a guard to the value that will only poison if a panic unwinds through the guard,
+    recovering it first if it was poisoned by an error.
+
+    This method is like [`Poison::on_unwind`], but treats errors as retryable and panics as bugs.
+    If the value was poisoned by an error, or by a guard that was dropped or forgotten without
+    being recovered, then it's recovered and a guard is returned. An error the value was poisoned
+    with is still available through [`Poison::last_poison`]. If the value was poisoned by a panic
+    then `Err` is returned with a recovery guard instead.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+    use std::io;
+
+    let mut v = Poison::new(42);
+
+    let guard = Poison::unless_recovered(&mut v).unwrap();
+    let _ = Poison::try_recover(Err::<(), _>(io::Error::other("connection reset")), guard);
+
+    assert!(v.is_poisoned());
+
+    // The error is cleared when the next guard is acquired
+    let guard = Poison::on_unwind_clearing_errors(&mut v).unwrap();
+
+    assert_eq!(42, *guard);
+    drop(guard);
+
+    assert!(!v.is_poisoned());
+    assert!(v.last_poison().is_some());
+    ```
+    */
+    #[track_caller]
+    pub fn on_unwind_clearing_errors<'a, Target>(
+        poison: Target,
+    ) -> Result<PoisonGuard<'a, T, Target>, PoisonRecover<'a, T, Target>>
+    where
+        Target: ops::DerefMut<Target = Poison<T>> + 'a,
+    {
+        if poison.is_poisoned_by_panic() || poison.is_frozen() {
+            Err(PoisonRecover::recover_to_poison_on_unwind(poison))
+        } else {
+            // Acquiring a guard clears any error the value was poisoned with
+            Ok(PoisonGuard::poison_on_unwind(poison))
+        }
+    }
+
+    /**
+    Get a guard to the value that will only poison if a panic unwinds through the guard, along
+    with the reason the value was last poisoned if it's since been recovered.
+
+    This method is like [`Poison::on_unwind`], but can be used to perform extra validation
+    on values that were previously poisoned and recovered.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(42);
+
+    // Poison the value by dropping a guard without recovering it
+    drop(Poison::unless_recovered(&mut v).unwrap());
+
+    // Recover the value
+    drop(Poison::on_unwind(&mut v).unwrap_err().recover());
+
+    let (guard, last_poisoned) = Poison::on_unwind_checked(&mut v).unwrap();
+
+    assert!(last_poisoned.is_some());
+    ```
+    */
+    #[track_caller]
+    #[allow(clippy::type_complexity)]
+    pub fn on_unwind_checked<'a, Target>(
+        poison: Target,
+    ) -> Result<(PoisonGuard<'a, T, Target>, Option<PoisonError>), PoisonRecover<'a, T, Target>>
+    where
+        Target: ops::DerefMut<Target = Poison<T>> + 'a,
+    {
+        let last_cleared = poison.state.last_cleared().cloned();
+
+        Poison::on_unwind(poison).map(|guard| (guard, last_cleared))
+    }
+
+    /**
+    Get a guard to the value that will immediately poison and only unpoison with [`Poison::recover`] or [`Poison::try_recover`].
+
+    This method is an alternative to [`Poison::on_unwind`] that can also protect state against early
+    returns through non-panicking control flow, like `?`.
+
+    ## Examples
+
+    Guarding a local variable or field:
+
+    ```
+    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
+    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(42);
+
+    let mut guard = Poison::unless_recovered(&mut v)?;
+
+    // If this call fails the value will remain poisoned
+    some_fallible_operation(&mut guard)?;
+
+    // If we get this far then the state is still valid, so return the guard and unpoison
+    Poison::recover(guard);
+    # Ok(())
+    # }
+    ```
+
+    Poisoning a mutex:
+
+    ```
+    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
+    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use poison_guard::Poison;
+    use parking_lot::Mutex;
+
+    let mutex = Mutex::new(Poison::new(42));
+
+    let mut guard = Poison::unless_recovered(mutex.lock())?;
+
+    // If this call fails the value will remain poisoned
+    some_fallible_operation(&mut guard)?;
+
+    // If we get this far then the state is still valid, so return the guard and unpoison
+    Poison::recover(guard);
+    # Ok(())
+    # }
+    ```
+    */
+    #[track_caller]
+    pub fn unless_recovered<'a, Target>(
+        poison: Target,
+    ) -> Result<PoisonGuard<'a, T, Target>, PoisonRecover<'a, T, Target>>
+    where
+        Target: ops::DerefMut<Target = Poison<T>> + 'a,
+    {
+        if poison.is_poisoned() || poison.is_frozen() {
+            Err(PoisonRecover::recover_to_poison_now(poison))
+        } else {
+            Ok(PoisonGuard::poison_now(poison))
+        }
+    }
+
+    /**
+    Recover a guard, unpoisoning it if it was poisoned.
+
+    This method must be used to recover guards acquired through [`Poison::unless_recovered`].
+    Guards acquired through [`Poison::on_unwind`] can also be returned this way, but it isn't
+    necessary to do so.
+
+    # Examples
+
+    Guarding a local variable or field:
+
+    ```
+    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
+    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(42);
+
+    let mut guard = Poison::unless_recovered(&mut v)?;
+
+    // If this call fails the value will remain poisoned
+    some_fallible_operation(&mut guard)?;
+
+    // If we get this far then the state is still valid, so return the guard and unpoison
+    Poison::recover(guard);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn recover<'a, Target>(guard: PoisonGuard<'a, T, Target>)
+    where
+        Target: ops::DerefMut<Target = Poison<T>> + 'a,
+    {
+        PoisonGuard::unpoison_now(guard);
+    }
+
+    /**
+    Try recover a guard based on a result.
+
+    This method can be used to wrap a call that operates on the underlying value. If the operation
+    fails then the value will be poisoned. This approach differs from [`Poison::recover`] by
+    capturing the error and storing it in the `Poison<T>`. Future attempts to access the value will
+    include the original error.
+
+    ## Examples
+
+    Guarding a local variable or field:
+
+    ```
+    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
+    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(42);
+
+    let mut guard = Poison::unless_recovered(&mut v)?;
+
+    // If this call fails the value will remain poisoned
+    // The error will be captured and a wrapper will be returned
+    Poison::try_recover(some_fallible_operation(&mut guard), guard)?;
+    # Ok(())
+    # }
     ```
     */
     #[track_caller]
@@ -493,4 +1593,273 @@ impl<T> Poison<T> {
             Err(err) => Err(PoisonGuard::poison_with_error(guard, err)),
         }
     }
+
+    /**
+    Try recover a guard based on a result, poisoning with a code categorizing the error if it
+    fails.
+
+    This method is like [`Poison::try_recover`]. The code can be read back through
+    [`PoisonError::code`].
+
+    ## Examples
+
+    ```
+    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Err("failed".into()) }
+    use poison_guard::Poison;
+
+    const OPERATION_FAILED: u32 = 7;
+
+    let mut v = Poison::new(42);
+
+    let mut guard = Poison::unless_recovered(&mut v).unwrap();
+
+    let r = some_fallible_operation(&mut guard);
+    let err = Poison::try_recover_with_code(r, guard, OPERATION_FAILED).unwrap_err();
+
+    assert_eq!(Some(OPERATION_FAILED), err.code());
+    ```
+    */
+    #[track_caller]
+    pub fn try_recover_with_code<Target, O, E>(
+        r: Result<O, E>,
+        guard: PoisonGuard<T, Target>,
+        code: u32,
+    ) -> Result<O, PoisonError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+        Target: ops::DerefMut<Target = Poison<T>>,
+    {
+        match r {
+            Ok(ok) => {
+                PoisonGuard::unpoison_now(guard);
+                Ok(ok)
+            }
+            Err(err) => Err(PoisonGuard::poison_with_error_code(guard, err, code)),
+        }
+    }
+
+    /**
+    Poison a guard with an error and some additional context.
+
+    The context will be included when the returned error is displayed, so it can be used to
+    describe what was happening when the value was poisoned.
+
+    ## Examples
+
+    ```
+    # fn compact_segment(_: &mut Vec<u8>, _: usize) -> Result<(), std::io::Error> { Err(std::io::ErrorKind::Other.into()) }
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(Vec::new());
+
+    let mut guard = Poison::unless_recovered(&mut v).unwrap();
+
+    if let Err(e) = compact_segment(&mut guard, 5) {
+        let err = Poison::err_context(guard, e, "while compacting segment 5");
+
+        assert!(err.to_string().starts_with("while compacting segment 5"));
+    }
+    ```
+    */
+    #[track_caller]
+    pub fn err_context<Target, E>(
+        guard: PoisonGuard<T, Target>,
+        e: E,
+        context: impl Into<Cow<'static, str>>,
+    ) -> PoisonError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+        Target: ops::DerefMut<Target = Poison<T>>,
+    {
+        PoisonGuard::poison_with_error_context(guard, e, context.into())
+    }
+}
+
+impl<T> Poison<Option<T>> {
+    /**
+    Try get the inner optional value, dereferencing it if it's present.
+
+    This will return `Err` if the value is poisoned.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = Poison::new(None::<String>);
+
+    assert_eq!(None, conn.as_deref()?);
+
+    *Poison::on_unwind(&mut conn).unwrap() = Some(String::from("localhost:8080"));
+
+    assert_eq!(Some("localhost:8080"), conn.as_deref()?);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn as_deref(&self) -> Result<Option<&T::Target>, PoisonError>
+    where
+        T: ops::Deref,
+    {
+        if self.is_poisoned() {
+            Err(self.state.to_error())
+        } else {
+            Ok(self.value.as_deref())
+        }
+    }
+
+    /**
+    Try get the inner optional value, initializing it if it's not present.
+
+    If the value is `None` then `init` is called to initialize it. If `init` panics then the
+    panic is caught and the value is poisoned with it. Later calls will return the initialized
+    value without calling `init` again.
+
+    This will return `Err` if the value is poisoned, or if it's frozen before being initialized.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut primes = Poison::new(None);
+
+    let sum: u32 = primes.get_or_init(|| vec![2, 3, 5, 7]).map(|primes| primes.iter().sum())?;
+
+    assert_eq!(17, sum);
+    # Ok(())
+    # }
+    ```
+    */
+    #[track_caller]
+    pub fn get_or_init(&mut self, init: impl FnOnce() -> T) -> Result<&T, PoisonError> {
+        if self.is_poisoned() {
+            return Err(self.state.to_error());
+        }
+
+        if self.value.is_none() {
+            if self.is_frozen() {
+                return Err(self.state.to_error());
+            }
+
+            match panic::catch_unwind(panic::AssertUnwindSafe(init)) {
+                Ok(value) => self.value = Some(value),
+                Err(panic) => {
+                    self.state.poison_with_panic(Some(panic));
+
+                    return Err(self.state.to_error());
+                }
+            }
+        }
+
+        Ok(self
+            .value
+            .as_ref()
+            .expect("the value is always initialized"))
+    }
+}
+
+impl<T> Default for Poison<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Poison::new(Default::default())
+    }
+}
+
+impl<T> From<T> for Poison<T> {
+    fn from(v: T) -> Self {
+        Poison::new(v)
+    }
+}
+
+/**
+Run a function on a value, poisoning it if the function panics.
+
+This is equivalent to acquiring a guard through [`Poison::on_unwind`] and running the function
+on it. If the value is already poisoned then the function isn't called and the reason the value
+was poisoned is returned instead. If the function panics then the value is poisoned and the
+panic continues to unwind.
+
+## Examples
+
+```
+use poison_guard::{Poison, PoisonError};
+
+struct Account(Poison<AccountState>);
+
+struct AccountState {
+    total: i64,
+    // Invariant: the total must be the sum of the changes
+    changes: Vec<i64>,
+}
+
+impl Account {
+    pub fn push_change(&mut self, change: i64) -> Result<(), PoisonError> {
+        poison_guard::catch(&mut self.0, |state| {
+            state.changes.push(change);
+
+            // If we panic here then our state is invalid
+            // Future calls to `push_change` will return an error
+
+            state.total += change;
+        })
+    }
+}
+```
+*/
+#[track_caller]
+pub fn catch<T, R>(poison: &mut Poison<T>, f: impl FnOnce(&mut T) -> R) -> Result<R, PoisonError> {
+    let mut guard = Poison::on_unwind(poison)?;
+
+    Ok(f(&mut guard))
+}
+
+/**
+Run a function on a value, running some cleanup on it if the function panics.
+
+This is like [`catch`], but for values that aren't wrapped in a `Poison<T>`. Instead of
+poisoning the value, `on_unwind` is called with it if `f` panics, and then the panic
+continues to unwind. This can be used to restore invariants or release resources held by
+the value before the panic propagates.
+
+## Examples
+
+```
+use std::panic;
+
+let mut pending = vec![1, 2, 3];
+
+let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+    poison_guard::guard_scope(
+        &mut pending,
+        |pending| {
+            pending.push(4);
+
+            panic!("explicit panic");
+        },
+        // If we panic then don't leave any partially processed work behind
+        |pending| pending.clear(),
+    )
+}));
+
+assert!(pending.is_empty());
+```
+*/
+pub fn guard_scope<T, R>(
+    value: &mut T,
+    f: impl FnOnce(&mut T) -> R,
+    on_unwind: impl FnOnce(&mut T),
+) -> R {
+    match panic::catch_unwind(panic::AssertUnwindSafe(|| f(&mut *value))) {
+        Ok(r) => r,
+        Err(panic) => {
+            on_unwind(value);
+
+            panic::resume_unwind(panic)
+        }
+    }
 }
@@ -0,0 +1,89 @@
+/*!
+Poisoning with `anyhow::Error`s without losing their context.
+
+This module is only available with the `anyhow` feature.
+*/
+
+use std::{
+    error::Error,
+    fmt,
+    ops,
+};
+
+use super::{
+    Poison,
+    PoisonError,
+    PoisonGuard,
+};
+
+/**
+An `anyhow::Error` stored as the reason a value was poisoned.
+
+Converting an `anyhow::Error` into a `Box<dyn Error>` loses its type, so it can't be recovered
+along with its backtrace later. This wrapper keeps the original error around instead.
+*/
+struct AnyhowError(anyhow::Error);
+
+impl fmt::Debug for AnyhowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for AnyhowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl Error for AnyhowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        // Skip over the outermost error, since it's displayed by this one
+        self.0.source()
+    }
+}
+
+impl<T> Poison<T> {
+    /**
+    Poison a guard with an `anyhow::Error`.
+
+    The error is stored as-is, so its context chain and backtrace can be retrieved later
+    through [`PoisonError::as_anyhow`].
+
+    ## Examples
+
+    ```
+    use anyhow::Context;
+    use poison_guard::{Poison, PoisonError};
+
+    let mut v = Poison::new(Vec::<u8>::new());
+
+    let guard = Poison::unless_recovered(&mut v).unwrap();
+
+    let err = std::fs::read("missing.txt").context("while loading segment 5").unwrap_err();
+
+    Poison::err_anyhow(guard, err);
+
+    let err = PoisonError::from(v.get().unwrap_err());
+
+    assert_eq!("while loading segment 5", err.as_anyhow().unwrap().to_string());
+    ```
+    */
+    #[track_caller]
+    pub fn err_anyhow<Target>(guard: PoisonGuard<T, Target>, err: anyhow::Error) -> PoisonError
+    where
+        Target: ops::DerefMut<Target = Poison<T>>,
+    {
+        PoisonGuard::poison_with_error(guard, AnyhowError(err))
+    }
+}
+
+impl PoisonError {
+    /**
+    Get the `anyhow::Error` the value was poisoned with, if it was poisoned through
+    [`Poison::err_anyhow`].
+    */
+    pub fn as_anyhow(&self) -> Option<&anyhow::Error> {
+        self.downcast_ref::<AnyhowError>().map(|err| &err.0)
+    }
+}
@@ -0,0 +1,82 @@
+/*!
+Owned guards for values protected by an `Arc<parking_lot::Mutex<Poison<T>>>`.
+
+This module is only available with the `parking_lot` feature.
+*/
+
+use parking_lot::{
+    ArcMutexGuard,
+    Mutex,
+    RawMutex,
+};
+use std::sync::Arc;
+
+use super::{
+    Poison,
+    PoisonGuard,
+    PoisonRecover,
+};
+
+/**
+A guard for a valid value that holds its lock through an `Arc`.
+
+Owned guards aren't tied to the lifetime of a borrow, so they can be returned from functions or
+moved into other tasks.
+*/
+pub type OwnedPoisonGuard<T> = PoisonGuard<'static, T, ArcMutexGuard<RawMutex, Poison<T>>>;
+
+/**
+A guard for a poisoned value that holds its lock through an `Arc`.
+*/
+pub type OwnedPoisonRecover<T> = PoisonRecover<'static, T, ArcMutexGuard<RawMutex, Poison<T>>>;
+
+impl<T> Poison<T>
+where
+    T: 'static,
+{
+    /**
+    Lock a shared value and get an owned guard to it that will only poison if a panic unwinds
+    through the guard.
+
+    This method is like [`Poison::on_unwind`], but the guard holds a clone of the `Arc`, so
+    it isn't tied to the lifetime of `mutex`.
+
+    ## Examples
+
+    ```
+    use parking_lot::Mutex;
+    use poison_guard::{OwnedPoisonGuard, Poison};
+    use std::sync::Arc;
+
+    fn lock(shared: &Arc<Mutex<Poison<Vec<i32>>>>) -> OwnedPoisonGuard<Vec<i32>> {
+        Poison::on_unwind_arc(shared).unwrap()
+    }
+
+    let shared = Arc::new(Mutex::new(Poison::new(Vec::new())));
+
+    let mut guard = lock(&shared);
+
+    guard.push(42);
+    ```
+    */
+    #[track_caller]
+    pub fn on_unwind_arc(
+        mutex: &Arc<Mutex<Poison<T>>>,
+    ) -> Result<OwnedPoisonGuard<T>, OwnedPoisonRecover<T>> {
+        Poison::on_unwind(mutex.lock_arc())
+    }
+
+    /**
+    Lock a shared value and get an owned guard to it that will immediately poison and only
+    unpoison with [`Poison::recover`] or [`Poison::try_recover`].
+
+    This method is like [`Poison::unless_recovered`], but the guard holds a clone of the `Arc`,
+    so it isn't tied to the lifetime of `mutex`.
+    */
+    #[track_caller]
+    pub fn unless_recovered_arc(
+        mutex: &Arc<Mutex<Poison<T>>>,
+    ) -> Result<OwnedPoisonGuard<T>, OwnedPoisonRecover<T>> {
+        Poison::unless_recovered(mutex.lock_arc())
+    }
+}
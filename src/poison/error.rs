@@ -4,16 +4,318 @@ use std::{
     error::Error,
     fmt,
     mem,
-    panic::Location,
-    sync::Arc,
+    panic::{
+        Location,
+        RefUnwindSafe,
+        UnwindSafe,
+    },
+    ptr,
+    sync::{
+        self,
+        Arc,
+    },
+    thread::{
+        self,
+        ThreadId,
+    },
+};
+
+use super::{
+    hook,
+    stats::PoisonStats,
 };
 
 /**
 An error indicating that a value was poisoned.
+
+## Formatting
+
+The default `Display` format includes where the value was poisoned, which is useful for logs.
+The alternate format `{:#}` omits it, which is better suited for messages shown to users.
+
+```
+use poison_guard::{Poison, PoisonError};
+
+let mut v = Poison::new(42);
+drop(Poison::unless_recovered(&mut v).unwrap());
+
+let err = PoisonError::from(v.get().unwrap_err());
+
+assert_eq!("poisoned by an error", format!("{:#}", err));
+```
 */
 #[derive(Clone)]
 pub struct PoisonError(PoisonStateInner);
 
+impl PoisonError {
+    /**
+    Whether the value was poisoned by a panic with a message that was captured.
+
+    Panic messages are captured when the panic payload is a `&'static str` or `String`,
+    which is the case for panics raised through the `panic!` macro.
+    */
+    pub fn is_captured_panic(&self) -> bool {
+        match self.0 {
+            PoisonStateInner::CapturedPanic(_) => true,
+            PoisonStateInner::Restored(ref record) => record.panic && record.message.is_some(),
+            _ => false,
+        }
+    }
+
+    /**
+    Whether the value was poisoned by a panic with a payload that couldn't be captured.
+
+    This is the case for panics that unwind through a guard, and for panics raised with a
+    payload that isn't a string, like through [`std::panic::panic_any`].
+    */
+    pub fn is_unknown_panic(&self) -> bool {
+        match self.0 {
+            PoisonStateInner::UnknownPanic(_) => true,
+            PoisonStateInner::Restored(ref record) => record.panic && record.message.is_none(),
+            _ => false,
+        }
+    }
+
+    /**
+    Get the error the value was poisoned with, if it's of type `E`.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonError};
+    use std::io;
+
+    let mut v = Poison::new(42);
+
+    v.poison_with(io::Error::from(io::ErrorKind::ConnectionReset)).unwrap();
+
+    let err = PoisonError::from(v.get().unwrap_err());
+
+    assert_eq!(
+        io::ErrorKind::ConnectionReset,
+        err.downcast_ref::<io::Error>().unwrap().kind()
+    );
+    ```
+    */
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: Error + 'static,
+    {
+        if let PoisonStateInner::CapturedErr(ref err) = self.0 {
+            // If the value was poisoned while recovering then look at the error that caused it
+            if let Some(err) = err.source.downcast_ref::<FailedRecovery>() {
+                return err.source.downcast_ref();
+            }
+
+            err.source.downcast_ref()
+        } else {
+            None
+        }
+    }
+
+    /**
+    Get the message the value was poisoned with.
+
+    For values poisoned by a panic with a captured message this is the panic message. For values
+    poisoned by an error this is the formatted error. Other values don't have a message.
+
+    The message is formatted on its own, without the extra details included when displaying the
+    `PoisonError`, like where the value was poisoned.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonError};
+
+    let v: Poison<i32> = Poison::new_catch_unwind(|| panic!("explicit panic"));
+
+    let err = PoisonError::from(v.get().unwrap_err());
+
+    assert_eq!(Some("explicit panic"), err.message().as_deref());
+    ```
+    */
+    pub fn message(&self) -> Option<Cow<'_, str>> {
+        match self.0 {
+            PoisonStateInner::CapturedPanic(ref panic) => Some(Cow::Borrowed(&*panic.payload)),
+            PoisonStateInner::CapturedErr(ref err) => Some(Cow::Owned(err.source.to_string())),
+            PoisonStateInner::Restored(ref record) => record.message.as_deref().map(Cow::Borrowed),
+            _ => None,
+        }
+    }
+
+    /**
+    Get a record of this error that can be persisted and later restored.
+
+    The record contains whether the value was poisoned by a panic, its message, its code, and the
+    location it was poisoned at. Other details, like the poisoning thread or the error source, aren't
+    retained. This method returns `None` if the error doesn't represent a poisoned value, like
+    when a value is frozen.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonError};
+
+    let mut v = Poison::new(42);
+    v.poison_with("the value is no longer valid").unwrap();
+
+    let record = PoisonError::from(v.get().unwrap_err()).to_record().unwrap();
+
+    assert!(!record.is_panic());
+    assert_eq!(Some("the value is no longer valid"), record.message());
+    ```
+    */
+    pub fn to_record(&self) -> Option<PoisonRecord> {
+        if let PoisonStateInner::Restored(ref record) = self.0 {
+            return Some((**record).clone());
+        }
+
+        let location = self.0.location()?;
+
+        Some(PoisonRecord {
+            panic: self.0.is_panic(),
+            message: self.message().map(Cow::into_owned),
+            code: self.code(),
+            file: location.file().to_owned(),
+            line: location.line(),
+            column: location.column(),
+        })
+    }
+
+    /**
+    Restore an error from a record.
+
+    See [`PoisonError::to_record`] for details.
+    */
+    pub fn from_record(record: PoisonRecord) -> Self {
+        PoisonError(PoisonStateInner::Restored(Arc::new(record)))
+    }
+
+    /**
+    Get the code the value was poisoned with, if there is one.
+
+    Codes can be given when poisoning a value through methods like [`Poison::poison_with_code`]
+    and [`Poison::try_recover_with_code`]. They can be used to categorize failures without
+    downcasting the error they were poisoned with.
+
+    [`Poison::poison_with_code`]: crate::Poison::poison_with_code
+    [`Poison::try_recover_with_code`]: crate::Poison::try_recover_with_code
+    */
+    pub fn code(&self) -> Option<u32> {
+        match self.0 {
+            PoisonStateInner::CapturedErr(ref err) => err.code,
+            PoisonStateInner::Restored(ref record) => record.code,
+            _ => None,
+        }
+    }
+
+    /**
+    Add context to the reason the value was poisoned.
+
+    The context is shown before the error when it's displayed. Adding context to an error that
+    already has some nests it, so the most recently added context is shown first. The error
+    source, code, poisoning thread, and location are all retained, so [`PoisonError::downcast_ref`]
+    and [`Error::source`] still see the original error.
+
+    Only values poisoned by an error can carry context. Other errors, like panics, are returned
+    unchanged.
+
+    This is useful in combination with [`Poison::map_poison`].
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonError};
+    use std::io;
+
+    let mut v = Poison::new(42);
+    v.poison_with(io::Error::from(io::ErrorKind::InvalidData)).unwrap();
+
+    v.map_poison(|err| err.context("while loading segment 5"));
+
+    let err = PoisonError::from(v.get().unwrap_err());
+
+    assert!(err.to_string().starts_with("while loading segment 5: "));
+    assert!(err.downcast_ref::<io::Error>().is_some());
+    ```
+
+    [`Poison::map_poison`]: crate::Poison::map_poison
+    */
+    pub fn context(self, context: impl Into<Cow<'static, str>>) -> Self {
+        match self.0 {
+            PoisonStateInner::CapturedErr(ref err) => {
+                let context = match err.context {
+                    Some(ref inner) => Cow::Owned(format!("{}: {}", context.into(), inner)),
+                    None => context.into(),
+                };
+
+                PoisonError(PoisonStateInner::CapturedErr(Arc::new(CapturedErr {
+                    location: err.location,
+                    thread: err.thread.clone(),
+                    context: Some(context),
+                    code: err.code,
+                    source: err.source.clone(),
+                })))
+            }
+            _ => self,
+        }
+    }
+
+    /**
+    Get the id and name of the thread that poisoned the value.
+
+    This method will return `None` if the value was poisoned by a guard that was forgotten
+    through `mem::forget`, or if the error was converted from a [`std::sync::PoisonError`].
+    */
+    pub fn poisoning_thread(&self) -> Option<(ThreadId, Option<String>)> {
+        self.0
+            .thread()
+            .map(|thread| (thread.id, thread.name.clone()))
+    }
+
+    /**
+    Whether the value is likely to be recoverable.
+
+    Panics generally indicate a bug, so values poisoned by a panic aren't considered recoverable.
+    Frozen values also aren't recoverable.
+    Values poisoned by an error or a guard that wasn't recovered are considered recoverable.
+    This can be used to implement a policy of propagating panics but recovering errors.
+    */
+    pub fn is_recoverable(&self) -> bool {
+        !(self.0.is_panic() || matches!(self.0, PoisonStateInner::Frozen))
+    }
+
+    /**
+    Whether the guard couldn't be acquired because the value is frozen.
+
+    Frozen values aren't poisoned, but can't be modified.
+    See [`Poison::freeze`] for more details.
+
+    [`Poison::freeze`]: crate::Poison::freeze
+    */
+    pub fn is_frozen(&self) -> bool {
+        matches!(self.0, PoisonStateInner::Frozen)
+    }
+
+    /**
+    Whether the value was poisoned by a guard that was released without being recovered.
+
+    This is the case for guards acquired through [`Poison::unless_recovered`] that are dropped
+    without calling [`Poison::recover`], and for guards that are forgotten through `mem::forget`.
+    It isn't the case for values poisoned by an actual panic or a captured error.
+
+    [`Poison::unless_recovered`]: crate::Poison::unless_recovered
+    [`Poison::recover`]: crate::Poison::recover
+    */
+    pub fn was_guard_drop(&self) -> bool {
+        match self.0 {
+            PoisonStateInner::Guarded(_) | PoisonStateInner::Unrecovered(_) => true,
+            PoisonStateInner::UnknownErr(ref err) => err.dropped_guard,
+            _ => false,
+        }
+    }
+}
+
 impl fmt::Debug for PoisonError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Debug::fmt(&self.0, f)
@@ -30,10 +332,137 @@ impl Error for PoisonError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         Error::source(&self.0)
     }
+
+    #[cfg(poison_guard_unstable)]
+    fn provide<'a>(&'a self, request: &mut std::error::Request<'a>) {
+        if let Some(location) = self.0.location() {
+            request.provide_ref::<Location<'static>>(location);
+        }
+
+        // Also offer anything the original error provides, like its backtrace
+        if let Some(source) = Error::source(&self.0) {
+            source.provide(request);
+        }
+    }
 }
 
-#[derive(Clone)]
-pub(super) struct PoisonState(PoisonStateInner);
+/**
+Compare two errors by how and where they were poisoned.
+
+Errors are equal if they were poisoned the same way, like both by a panic with a captured
+message, at the same location. Panic messages, error sources, codes, and poisoning threads aren't
+compared. Errors restored from a [`PoisonRecord`] are compared by whether they were a panic and
+by their recorded location.
+*/
+impl PartialEq for PoisonError {
+    fn eq(&self, other: &Self) -> bool {
+        // Restored errors don't have a `'static` location, so compare the one in their records
+        if let (PoisonStateInner::Restored(a), PoisonStateInner::Restored(b)) = (&self.0, &other.0)
+        {
+            return a.panic == b.panic
+                && (&a.file, a.line, a.column) == (&b.file, b.line, b.column);
+        }
+
+        mem::discriminant(&self.0) == mem::discriminant(&other.0)
+            && self.0.location() == other.0.location()
+    }
+}
+
+impl Eq for PoisonError {}
+
+/**
+Convert a poisoned lock from the standard library into a `PoisonError`.
+
+The standard library doesn't retain the panic that poisoned the lock, or where it happened, so
+the resulting error is an unknown panic located where the conversion happened. The guard carried
+by the standard library's error is dropped, releasing the lock.
+
+## Examples
+
+```
+use poison_guard::PoisonError;
+use std::{sync::Mutex, thread};
+
+let mutex = Mutex::new(42);
+
+thread::scope(|scope| {
+    let _ = scope
+        .spawn(|| {
+            let _guard = mutex.lock().unwrap();
+            panic!("explicit panic");
+        })
+        .join();
+});
+
+let err = PoisonError::from(mutex.lock().unwrap_err());
+
+assert!(err.is_unknown_panic());
+```
+*/
+impl<G> From<sync::PoisonError<G>> for PoisonError {
+    #[track_caller]
+    fn from(_: sync::PoisonError<G>) -> Self {
+        PoisonError(PoisonStateInner::UnknownPanic(Arc::new(UnknownPanic {
+            location: Location::caller(),
+            thread: None,
+            converted: true,
+        })))
+    }
+}
+
+/**
+The poison state of a value.
+
+The state is packed into a single pointer, so values that are only ever guarded don't allocate:
+
+- A null pointer is a value that isn't guarded or poisoned.
+- A tagged pointer is the location of an active guard.
+- An untagged pointer is a boxed payload holding everything else, like the reason the value was
+  poisoned, its generation, and any observers or stats.
+
+Once the payload is allocated it's kept, and the locations of guards are stored in it too.
+*/
+pub(super) struct PoisonState(*mut ());
+
+const TAG_MASK: usize = 0b11;
+const TAG_GUARDED: usize = 0b01;
+const TAG_GUARDED_UNLESS_FORGOTTEN: usize = 0b10;
+
+// The tag is stored in the low bits of the pointer, so they must always be free
+const _: () = assert!(
+    mem::align_of::<Location<'static>>() > TAG_MASK
+        && mem::align_of::<PoisonStateData>() > TAG_MASK
+);
+
+// The state owns either a `Box<PoisonStateData>` or a `&'static Location<'static>`
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Box<PoisonStateData>>();
+    assert_send_sync::<&'static Location<'static>>();
+};
+
+// SAFETY: The state is only a pointer to either a boxed payload or a location, both `Send`
+unsafe impl Send for PoisonState {}
+// SAFETY: The state is only a pointer to either a boxed payload or a location, both `Sync`
+unsafe impl Sync for PoisonState {}
+
+enum PoisonStateRef<'a> {
+    Unpoisoned,
+    Guarded(&'static Location<'static>),
+    GuardedUnlessForgotten(&'static Location<'static>),
+    Data(&'a PoisonStateData),
+}
+
+struct PoisonStateData {
+    inner: PoisonStateInner,
+    generation: u64,
+    last_cleared: Option<PoisonError>,
+    observers: Vec<Observer>,
+    stats: Option<PoisonStats>,
+}
+
+pub(super) type Observer = Box<dyn Fn(&PoisonError) + Send + Sync>;
 
 #[derive(Clone)]
 enum PoisonStateInner {
@@ -42,129 +471,801 @@ enum PoisonStateInner {
     CapturedErr(Arc<CapturedErr>),
     UnknownErr(Arc<UnknownErr>),
     Guarded(&'static Location<'static>),
+    // Like `Guarded`, but the value isn't considered poisoned if the guard is forgotten
+    GuardedUnlessForgotten(&'static Location<'static>),
+    // Held by a guard that must be recovered, so the value is poisoned until it is
+    Unrecovered(&'static Location<'static>),
+    Restored(Arc<PoisonRecord>),
+    Frozen,
     Unpoisoned,
 }
 
 struct CapturedPanic {
     location: &'static Location<'static>,
+    thread: PoisoningThread,
     payload: Cow<'static, str>,
 }
 
+/**
+A record of a poisoned value that can be persisted and later restored.
+
+Records are created through [`PoisonError::to_record`] and restored through
+[`PoisonError::from_record`] or [`Poison::new_poisoned`]. They can be written to storage in any
+format through their accessors, and recreated through [`PoisonRecord::new`].
+
+[`Poison::new_poisoned`]: crate::Poison::new_poisoned
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoisonRecord {
+    panic: bool,
+    message: Option<String>,
+    code: Option<u32>,
+    file: String,
+    line: u32,
+    column: u32,
+}
+
+impl PoisonRecord {
+    /**
+    Create a record from its parts.
+    */
+    pub fn new(
+        panic: bool,
+        message: Option<String>,
+        file: impl Into<String>,
+        line: u32,
+        column: u32,
+    ) -> Self {
+        PoisonRecord {
+            panic,
+            message,
+            code: None,
+            file: file.into(),
+            line,
+            column,
+        }
+    }
+
+    /**
+    Set the code the value was poisoned with.
+    */
+    pub fn with_code(mut self, code: u32) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /**
+    Whether the value was poisoned by a panic.
+    */
+    pub fn is_panic(&self) -> bool {
+        self.panic
+    }
+
+    /**
+    The panic or error message the value was poisoned with, if there was one.
+    */
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /**
+    The code the value was poisoned with, if there was one.
+    */
+    pub fn code(&self) -> Option<u32> {
+        self.code
+    }
+
+    /**
+    The file the value was poisoned in.
+    */
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /**
+    The line the value was poisoned on.
+    */
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /**
+    The column the value was poisoned at.
+    */
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+}
+
 struct UnknownPanic {
     location: &'static Location<'static>,
+    // Panics adapted from `std::sync::PoisonError` don't know what thread they happened on
+    thread: Option<PoisoningThread>,
+    // Whether the location is where a `std::sync::PoisonError` was converted, rather than where
+    // the poisoning guard was acquired
+    converted: bool,
 }
 
 struct CapturedErr {
     location: &'static Location<'static>,
-    source: Box<dyn Error + Send + Sync>,
+    thread: PoisoningThread,
+    context: Option<Cow<'static, str>>,
+    code: Option<u32>,
+    // Shared so errors can be rebuilt with more context without taking the original
+    source: Arc<dyn Error + Send + Sync>,
 }
 
 struct UnknownErr {
     location: &'static Location<'static>,
+    thread: PoisoningThread,
+    // Whether the value was poisoned by dropping a guard that needed to be recovered
+    dropped_guard: bool,
+}
+
+#[derive(Debug, Clone)]
+struct PoisoningThread {
+    id: ThreadId,
+    name: Option<String>,
+}
+
+struct Origin<'a> {
+    location: &'static Location<'static>,
+    thread: Option<&'a PoisoningThread>,
+}
+
+/**
+An error returned while trying to recover a value that was already poisoned.
+
+The original reason the value was poisoned is retained as the source of this error.
+*/
+struct FailedRecovery {
+    source: Box<dyn Error + Send + Sync>,
+    previous: PoisonStateInner,
 }
 
 impl PoisonState {
     pub(super) fn from_unpoisoned() -> Self {
-        PoisonState(PoisonStateInner::Unpoisoned)
+        PoisonState(ptr::null_mut())
     }
 
     pub(super) fn from_err(
         location: &'static Location<'static>,
         err: Option<Box<dyn Error + Send + Sync>>,
     ) -> Self {
-        PoisonState(if let Some(err) = err {
-            PoisonStateInner::CapturedErr(Arc::new(CapturedErr {
-                location,
-                source: err,
-            }))
-        } else {
-            PoisonStateInner::UnknownErr(Arc::new(UnknownErr { location }))
-        })
+        let mut state = PoisonState::from_unpoisoned();
+        state.poison(PoisonStateInner::from_err(location, err));
+
+        state
     }
 
     pub(super) fn from_panic(
         location: &'static Location<'static>,
         panic: Option<Box<dyn Any + Send>>,
     ) -> Self {
-        let panic = panic.and_then(|mut panic| {
-            if let Some(msg) = panic.downcast_ref::<&'static str>() {
-                return Some(Cow::Borrowed(*msg));
+        let mut state = PoisonState::from_unpoisoned();
+        state.poison(PoisonStateInner::from_panic(location, panic));
+
+        state
+    }
+
+    #[track_caller]
+    pub(super) fn from_error(err: PoisonError) -> Self {
+        let inner = if err.0.is_failure() {
+            err.0
+        } else {
+            PoisonStateInner::from_err(Location::caller(), None)
+        };
+
+        let mut state = PoisonState::from_unpoisoned();
+        state.poison(inner);
+
+        state
+    }
+
+    #[inline]
+    fn get(&self) -> PoisonStateRef<'_> {
+        if self.0.is_null() {
+            return PoisonStateRef::Unpoisoned;
+        }
+
+        let tag = self.0.addr() & TAG_MASK;
+        let ptr = self.0.map_addr(|addr| addr & !TAG_MASK);
+
+        // SAFETY: Tagged pointers are always created from a `&'static Location<'static>`
+        // and untagged ones from a `Box<PoisonStateData>` owned by this state
+        unsafe {
+            match tag {
+                TAG_GUARDED => PoisonStateRef::Guarded(&*ptr.cast::<Location<'static>>()),
+                TAG_GUARDED_UNLESS_FORGOTTEN => {
+                    PoisonStateRef::GuardedUnlessForgotten(&*ptr.cast::<Location<'static>>())
+                }
+                _ => PoisonStateRef::Data(&*ptr.cast::<PoisonStateData>()),
             }
+        }
+    }
 
-            if let Some(msg) = panic.downcast_mut::<String>() {
-                return Some(Cow::Owned(mem::take(&mut *msg)));
+    #[cfg(test)]
+    pub(crate) fn is_allocated(&self) -> bool {
+        self.data().is_some()
+    }
+
+    #[inline]
+    fn set_guarded(&mut self, location: &'static Location<'static>, tag: usize) {
+        self.0 = (location as *const Location<'static>)
+            .cast_mut()
+            .cast::<()>()
+            .map_addr(|addr| addr | tag);
+    }
+
+    #[inline]
+    fn data(&self) -> Option<&PoisonStateData> {
+        match self.get() {
+            PoisonStateRef::Data(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn inner(&self) -> Cow<'_, PoisonStateInner> {
+        match self.get() {
+            PoisonStateRef::Unpoisoned => Cow::Owned(PoisonStateInner::Unpoisoned),
+            PoisonStateRef::Guarded(location) => Cow::Owned(PoisonStateInner::Guarded(location)),
+            PoisonStateRef::GuardedUnlessForgotten(location) => {
+                Cow::Owned(PoisonStateInner::GuardedUnlessForgotten(location))
             }
+            PoisonStateRef::Data(data) => Cow::Borrowed(&data.inner),
+        }
+    }
 
-            None
-        });
+    fn data_mut(&mut self) -> &mut PoisonStateData {
+        if self.data().is_none() {
+            let data = Box::new(PoisonStateData {
+                inner: self.inner().into_owned(),
+                generation: 0,
+                last_cleared: None,
+                observers: Vec::new(),
+                stats: None,
+            });
 
-        PoisonState(if let Some(panic) = panic {
-            PoisonStateInner::CapturedPanic(Arc::new(CapturedPanic {
-                location,
-                payload: panic,
-            }))
-        } else {
-            PoisonStateInner::UnknownPanic(Arc::new(UnknownPanic { location }))
-        })
+            self.0 = Box::into_raw(data).cast::<()>();
+        }
+
+        // SAFETY: The state is an untagged pointer to a `Box<PoisonStateData>` it owns
+        unsafe { &mut *self.0.cast::<PoisonStateData>() }
+    }
+
+    #[inline]
+    fn guarded_location(&self) -> Option<&'static Location<'static>> {
+        match self.get() {
+            PoisonStateRef::Guarded(location)
+            | PoisonStateRef::GuardedUnlessForgotten(location) => Some(location),
+            PoisonStateRef::Data(data) => match data.inner {
+                PoisonStateInner::Guarded(location)
+                | PoisonStateInner::GuardedUnlessForgotten(location)
+                | PoisonStateInner::Unrecovered(location) => Some(location),
+                _ => None,
+            },
+            PoisonStateRef::Unpoisoned => None,
+        }
     }
 
+    #[inline]
     #[track_caller]
     pub(super) fn guarded(&mut self) {
-        *self = PoisonState(PoisonStateInner::Guarded(Location::caller()))
+        self.guarded_at(Location::caller());
+    }
+
+    #[inline]
+    pub(super) fn guarded_at(&mut self, location: &'static Location<'static>) {
+        self.clear(PoisonStateInner::Guarded(location));
+    }
+
+    #[inline]
+    #[track_caller]
+    pub(super) fn guarded_unless_forgotten(&mut self) {
+        self.clear(PoisonStateInner::GuardedUnlessForgotten(Location::caller()));
+    }
+
+    #[inline]
+    #[track_caller]
+    pub(super) fn guarded_unless_recovered(&mut self) {
+        self.guarded_unless_recovered_at(Location::caller());
+    }
+
+    pub(super) fn guarded_unless_recovered_at(&mut self, location: &'static Location<'static>) {
+        // The value is poisoned for as long as the guard is held, but the generation isn't
+        // advanced and observers aren't notified unless the guard is released without being
+        // recovered
+        self.clear(PoisonStateInner::Unrecovered(location));
     }
 
     #[track_caller]
     pub(super) fn poison_with_error(&mut self, err: Option<Box<dyn Error + Send + Sync>>) {
-        let location = if let PoisonStateInner::Guarded(location) = self.0 {
-            location
+        let location = self.guarded_location().unwrap_or_else(Location::caller);
+
+        self.poison_with_error_at(location, err);
+    }
+
+    pub(super) fn poison_with_error_at(
+        &mut self,
+        location: &'static Location<'static>,
+        err: Option<Box<dyn Error + Send + Sync>>,
+    ) {
+        self.poison(PoisonStateInner::from_err(location, err));
+    }
+
+    #[track_caller]
+    pub(super) fn poison_with_error_context(
+        &mut self,
+        err: Box<dyn Error + Send + Sync>,
+        context: Cow<'static, str>,
+    ) {
+        let location = self.guarded_location().unwrap_or_else(Location::caller);
+
+        self.poison(PoisonStateInner::CapturedErr(Arc::new(CapturedErr {
+            location,
+            thread: PoisoningThread::current(),
+            context: Some(context),
+            code: None,
+            source: err.into(),
+        })));
+    }
+
+    pub(super) fn poison_with_error_code_at(
+        &mut self,
+        location: &'static Location<'static>,
+        err: Box<dyn Error + Send + Sync>,
+        code: u32,
+    ) {
+        self.poison(PoisonStateInner::CapturedErr(Arc::new(CapturedErr {
+            location,
+            thread: PoisoningThread::current(),
+            context: None,
+            code: Some(code),
+            source: err.into(),
+        })));
+    }
+
+    #[track_caller]
+    pub(super) fn poison_with_error_code(&mut self, err: Box<dyn Error + Send + Sync>, code: u32) {
+        let location = self.guarded_location().unwrap_or_else(Location::caller);
+
+        self.poison_with_error_code_at(location, err, code);
+    }
+
+    #[track_caller]
+    pub(super) fn poison_with_failed_recovery(&mut self, err: Box<dyn Error + Send + Sync>) {
+        let err = if self.is_poisoned() {
+            Box::new(FailedRecovery {
+                source: err,
+                previous: self.inner().into_owned(),
+            })
         } else {
-            Location::caller()
+            err
         };
 
-        *self = PoisonState::from_err(location, err);
+        self.poison_with_error(Some(err));
     }
 
     #[track_caller]
     pub(super) fn poison_with_panic(&mut self, panic: Option<Box<dyn Any + Send>>) {
-        let location = if let PoisonStateInner::Guarded(location) = self.0 {
-            location
-        } else {
-            Location::caller()
-        };
+        let location = self.guarded_location().unwrap_or_else(Location::caller);
+
+        self.poison(PoisonStateInner::from_panic(location, panic));
+    }
 
-        *self = PoisonState::from_panic(location, panic);
+    pub(super) fn poison_if_unrecovered(&mut self) {
+        if let PoisonStateInner::Unrecovered(location) = *self.inner() {
+            self.poison(PoisonStateInner::UnknownErr(Arc::new(UnknownErr {
+                location,
+                thread: PoisoningThread::current(),
+                dropped_guard: true,
+            })));
+        }
     }
 
+    pub(super) fn unpoison_if_unrecovered(&mut self) {
+        // The guard was recovered, so there's no poisoning to remember
+        if let PoisonStateInner::Unrecovered(_) = *self.inner() {
+            self.data_mut().inner = PoisonStateInner::Unpoisoned;
+        }
+    }
+
+    #[inline]
     #[track_caller]
     pub(super) fn unpoison_if_guarded(&mut self) {
-        if let PoisonStateInner::Guarded(_) = self.0 {
-            *self = PoisonState::from_unpoisoned();
+        if self.guarded_location().is_some() {
+            self.unpoison();
         }
     }
 
     #[track_caller]
     pub(super) fn unpoison(&mut self) {
-        *self = PoisonState::from_unpoisoned();
+        // A value that isn't guarded or poisoned has nothing to clear
+        if !self.0.is_null() {
+            self.clear(PoisonStateInner::Unpoisoned);
+        }
+    }
+
+    fn clear(&mut self, inner: PoisonStateInner) {
+        // Guards can be acquired and released without allocating a payload
+        if self.data().is_none() {
+            match inner {
+                PoisonStateInner::Unpoisoned => {
+                    self.0 = ptr::null_mut();
+                    return;
+                }
+                PoisonStateInner::Guarded(location) => {
+                    self.set_guarded(location, TAG_GUARDED);
+                    return;
+                }
+                PoisonStateInner::GuardedUnlessForgotten(location) => {
+                    self.set_guarded(location, TAG_GUARDED_UNLESS_FORGOTTEN);
+                    return;
+                }
+                _ => (),
+            }
+        }
+
+        let data = self.data_mut();
+
+        // A guard that had to be recovered was forgotten, so it counts as a poisoning
+        if let PoisonStateInner::Unrecovered(_) = data.inner {
+            data.generation = data.generation.wrapping_add(1);
+        }
+
+        // If the value was poisoned then keep the reason it was poisoned around
+        if data.inner.is_failure() {
+            data.last_cleared = Some(PoisonError(mem::replace(&mut data.inner, inner)));
+        } else {
+            data.inner = inner;
+        }
+    }
+
+    fn poison(&mut self, inner: PoisonStateInner) {
+        let data = self.data_mut();
+
+        // Frozen values can't be poisoned
+        if let PoisonStateInner::Frozen = data.inner {
+            return;
+        }
+
+        // Only count transitions from a valid value to a poisoned one
+        // Replacing the reason an already poisoned value was poisoned doesn't count
+        let transitioned = !data.inner.is_failure();
+
+        // Guards that must be recovered mark the value as poisoned while they're held, but
+        // that only counts once they fail
+        let unrecovered = matches!(data.inner, PoisonStateInner::Unrecovered(_));
+
+        data.inner = inner;
+
+        if transitioned || unrecovered {
+            data.generation = data.generation.wrapping_add(1);
+
+            if let Some(ref stats) = data.stats {
+                stats.poisoned();
+            }
+
+            if !data.observers.is_empty() {
+                let err = PoisonError(data.inner.clone());
+
+                for observer in &data.observers {
+                    observer(&err);
+                }
+            }
+
+            hook::poisoned(|| self.to_error());
+        }
     }
 
+    #[track_caller]
+    pub(super) fn map_error(&mut self, f: impl FnOnce(PoisonError) -> PoisonError) {
+        if !self.is_poisoned() {
+            return;
+        }
+
+        let err = f(self.to_error());
+
+        // The value must stay poisoned regardless of what error was returned
+        let inner = if err.0.is_failure() {
+            err.0
+        } else {
+            let location = self.location().unwrap_or_else(Location::caller);
+
+            PoisonStateInner::from_err(location, None)
+        };
+
+        self.data_mut().inner = inner;
+    }
+
+    pub(super) fn with_stats() -> Self {
+        let mut state = PoisonState::from_unpoisoned();
+        state.data_mut().stats = Some(PoisonStats::default());
+
+        state
+    }
+
+    pub(super) fn stats(&self) -> Option<&PoisonStats> {
+        self.data().and_then(|data| data.stats.as_ref())
+    }
+
+    pub(super) fn observe(&mut self, observer: Observer) {
+        self.data_mut().observers.push(observer);
+    }
+
+    pub(super) fn location(&self) -> Option<&'static Location<'static>> {
+        self.inner().location()
+    }
+
+    pub(super) fn generation(&self) -> u64 {
+        self.data().map_or(0, |data| match data.inner {
+            // The value can only be observed while a guard that had to be recovered is marking
+            // it if that guard was forgotten, so it counts as a poisoning
+            PoisonStateInner::Unrecovered(_) => data.generation.wrapping_add(1),
+            _ => data.generation,
+        })
+    }
+
+    pub(super) fn last_cleared(&self) -> Option<&PoisonError> {
+        self.data().and_then(|data| data.last_cleared.as_ref())
+    }
+
+    pub(super) fn freeze(&mut self) -> Result<(), PoisonError> {
+        if self.is_poisoned() {
+            return Err(self.to_error());
+        }
+
+        self.data_mut().inner = PoisonStateInner::Frozen;
+
+        Ok(())
+    }
+
+    #[inline]
+    pub(super) fn is_frozen(&self) -> bool {
+        self.data()
+            .is_some_and(|data| matches!(data.inner, PoisonStateInner::Frozen))
+    }
+
+    #[inline]
     pub(super) fn is_unpoisoned(&self) -> bool {
-        matches!(self.0, PoisonStateInner::Unpoisoned)
+        match self.get() {
+            PoisonStateRef::Unpoisoned | PoisonStateRef::GuardedUnlessForgotten(_) => true,
+            PoisonStateRef::Guarded(_) => false,
+            PoisonStateRef::Data(data) => matches!(
+                data.inner,
+                PoisonStateInner::Unpoisoned
+                    | PoisonStateInner::GuardedUnlessForgotten(_)
+                    | PoisonStateInner::Frozen
+            ),
+        }
     }
 
+    #[inline]
     pub(super) fn is_poisoned(&self) -> bool {
         !self.is_unpoisoned()
     }
 
+    pub(super) fn is_poisoned_by_panic(&self) -> bool {
+        self.inner().is_panic()
+    }
+
+    // Compares the identity of the reason rather than its contents
+    // Poisoning the value again with an equal error isn't the same reason
+    pub(super) fn is_poisoned_by(&self, err: &PoisonError) -> bool {
+        match (&*self.inner(), &err.0) {
+            (PoisonStateInner::CapturedPanic(a), PoisonStateInner::CapturedPanic(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            (PoisonStateInner::UnknownPanic(a), PoisonStateInner::UnknownPanic(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            (PoisonStateInner::CapturedErr(a), PoisonStateInner::CapturedErr(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            (PoisonStateInner::UnknownErr(a), PoisonStateInner::UnknownErr(b)) => Arc::ptr_eq(a, b),
+            (PoisonStateInner::Restored(a), PoisonStateInner::Restored(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
     pub(super) fn to_error(&self) -> PoisonError {
-        PoisonError(self.0.clone())
+        PoisonError(self.inner().into_owned())
     }
 
     pub(super) fn as_dyn_error(&self) -> &(dyn Error + Send + Sync + 'static) {
-        &self.0
+        match self.get() {
+            PoisonStateRef::Data(data) => &data.inner,
+            PoisonStateRef::Guarded(location) => GuardedAt::new(location),
+            PoisonStateRef::Unpoisoned | PoisonStateRef::GuardedUnlessForgotten(_) => {
+                &PoisonStateInner::Unpoisoned
+            }
+        }
     }
 
     pub(super) fn to_dyn_error(&self) -> Box<dyn Error + Send + Sync> {
-        Box::new(self.0.clone())
+        Box::new(self.inner().into_owned())
+    }
+}
+
+impl Drop for PoisonState {
+    fn drop(&mut self) {
+        if let PoisonStateRef::Data(_) = self.get() {
+            // SAFETY: The state is an untagged pointer to a `Box<PoisonStateData>` it owns
+            drop(unsafe { Box::from_raw(self.0.cast::<PoisonStateData>()) });
+        }
+    }
+}
+
+/**
+The location of a guard that's poisoning a value, borrowed as an error.
+
+Guards that haven't poisoned their value don't allocate a payload to store their state in, so
+this wrapper lets their location be borrowed as an error directly.
+*/
+#[repr(transparent)]
+struct GuardedAt(Location<'static>);
+
+impl GuardedAt {
+    fn new(location: &'static Location<'static>) -> &'static GuardedAt {
+        // SAFETY: `GuardedAt` is a transparent wrapper around a `Location`
+        unsafe { &*(location as *const Location<'static>).cast::<GuardedAt>() }
+    }
+}
+
+impl fmt::Debug for GuardedAt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PoisonState")
+            .field("location", &&self.0)
+            .finish()
+    }
+}
+
+impl fmt::Display for GuardedAt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_guarded(&self.0, f)
+    }
+}
+
+impl Error for GuardedAt {}
+
+fn fmt_guarded(location: &Location, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str("poisoned")?;
+
+    // The alternate format `{:#}` omits where the value was poisoned
+    if !f.alternate() {
+        write!(f, " (the poisoning guard was acquired at '{}')", location)?;
+    }
+
+    Ok(())
+}
+
+impl Default for PoisonState {
+    fn default() -> Self {
+        PoisonState::from_unpoisoned()
+    }
+}
+
+// The state only holds diagnostics about why a value was poisoned, and it's always consistent
+// between transitions. Whether a `Poison<T>` is unwind safe only depends on its `T`.
+impl UnwindSafe for PoisonState {}
+impl RefUnwindSafe for PoisonState {}
+
+impl PoisonStateInner {
+    fn from_err(
+        location: &'static Location<'static>,
+        err: Option<Box<dyn Error + Send + Sync>>,
+    ) -> Self {
+        if let Some(err) = err {
+            PoisonStateInner::CapturedErr(Arc::new(CapturedErr {
+                location,
+                thread: PoisoningThread::current(),
+                context: None,
+                code: None,
+                source: err.into(),
+            }))
+        } else {
+            PoisonStateInner::UnknownErr(Arc::new(UnknownErr {
+                location,
+                thread: PoisoningThread::current(),
+                dropped_guard: false,
+            }))
+        }
+    }
+
+    fn from_panic(
+        location: &'static Location<'static>,
+        panic: Option<Box<dyn Any + Send>>,
+    ) -> Self {
+        let panic = panic.and_then(|mut panic| {
+            if let Some(msg) = panic.downcast_ref::<&'static str>() {
+                return Some(Cow::Borrowed(*msg));
+            }
+
+            if let Some(msg) = panic.downcast_mut::<String>() {
+                return Some(Cow::Owned(mem::take(&mut *msg)));
+            }
+
+            None
+        });
+
+        if let Some(panic) = panic {
+            PoisonStateInner::CapturedPanic(Arc::new(CapturedPanic {
+                location,
+                thread: PoisoningThread::current(),
+                payload: panic,
+            }))
+        } else {
+            PoisonStateInner::UnknownPanic(Arc::new(UnknownPanic {
+                location,
+                thread: Some(PoisoningThread::current()),
+                converted: false,
+            }))
+        }
+    }
+}
+
+impl PoisonStateInner {
+    #[inline]
+    fn is_failure(&self) -> bool {
+        !matches!(
+            self,
+            PoisonStateInner::Unpoisoned
+                | PoisonStateInner::Guarded(_)
+                | PoisonStateInner::GuardedUnlessForgotten(_)
+                | PoisonStateInner::Frozen
+        )
+    }
+
+    fn is_panic(&self) -> bool {
+        match self {
+            PoisonStateInner::CapturedPanic(_) | PoisonStateInner::UnknownPanic(_) => true,
+            PoisonStateInner::Restored(record) => record.panic,
+            _ => false,
+        }
+    }
+
+    fn location(&self) -> Option<&'static Location<'static>> {
+        match self {
+            PoisonStateInner::CapturedPanic(panic) => Some(panic.location),
+            PoisonStateInner::UnknownPanic(panic) => Some(panic.location),
+            PoisonStateInner::CapturedErr(err) => Some(err.location),
+            PoisonStateInner::UnknownErr(err) => Some(err.location),
+            PoisonStateInner::Guarded(location)
+            | PoisonStateInner::GuardedUnlessForgotten(location)
+            | PoisonStateInner::Unrecovered(location) => Some(location),
+            PoisonStateInner::Restored(_)
+            | PoisonStateInner::Frozen
+            | PoisonStateInner::Unpoisoned => None,
+        }
+    }
+
+    fn thread(&self) -> Option<&PoisoningThread> {
+        match self {
+            PoisonStateInner::CapturedPanic(panic) => Some(&panic.thread),
+            PoisonStateInner::UnknownPanic(panic) => panic.thread.as_ref(),
+            PoisonStateInner::CapturedErr(err) => Some(&err.thread),
+            PoisonStateInner::UnknownErr(err) => Some(&err.thread),
+            PoisonStateInner::Guarded(_)
+            | PoisonStateInner::GuardedUnlessForgotten(_)
+            | PoisonStateInner::Unrecovered(_)
+            | PoisonStateInner::Restored(_)
+            | PoisonStateInner::Frozen
+            | PoisonStateInner::Unpoisoned => None,
+        }
+    }
+}
+
+impl PoisoningThread {
+    fn current() -> Self {
+        let thread = thread::current();
+
+        PoisoningThread {
+            id: thread.id(),
+            name: thread.name().map(Into::into),
+        }
     }
 }
 
@@ -173,27 +1274,59 @@ impl fmt::Debug for PoisonStateInner {
         match self {
             PoisonStateInner::CapturedPanic(panic) => f
                 .debug_struct("PoisonState")
-                .field(&"panic", &panic.payload)
-                .field(&"location", &panic.location)
+                .field("panic", &panic.payload)
+                .field("location", &panic.location)
+                .field("thread", &panic.thread)
                 .finish(),
             PoisonStateInner::UnknownPanic(panic) => f
                 .debug_struct("PoisonState")
-                .field(&"panic", &"<unknown>")
-                .field(&"location", &panic.location)
-                .finish(),
-            PoisonStateInner::CapturedErr(err) => f
-                .debug_struct("PoisonState")
-                .field(&"err", &err.source)
-                .field(&"location", &err.location)
+                .field("panic", &"<unknown>")
+                .field("location", &panic.location)
+                .field("thread", &panic.thread)
                 .finish(),
+            PoisonStateInner::CapturedErr(err) => {
+                let mut f = f.debug_struct("PoisonState");
+
+                if let Some(ref context) = err.context {
+                    f.field("context", context);
+                }
+
+                if let Some(ref code) = err.code {
+                    f.field("code", code);
+                }
+
+                f.field("err", &err.source)
+                    .field("location", &err.location)
+                    .field("thread", &err.thread)
+                    .finish()
+            }
             PoisonStateInner::UnknownErr(err) => f
                 .debug_struct("PoisonState")
-                .field(&"err", &"<unknown>")
-                .field(&"location", &err.location)
+                .field("err", &"<unknown>")
+                .field("location", &err.location)
+                .field("thread", &err.thread)
                 .finish(),
             PoisonStateInner::Guarded(location) => f
                 .debug_struct("PoisonState")
-                .field(&"location", &location)
+                .field("location", &location)
+                .finish(),
+            PoisonStateInner::GuardedUnlessForgotten(location) => f
+                .debug_struct("PoisonState")
+                .field("location", &location)
+                .field("poisons_on_forget", &false)
+                .finish(),
+            PoisonStateInner::Unrecovered(location) => f
+                .debug_struct("PoisonState")
+                .field("location", &location)
+                .field("unrecovered", &true)
+                .finish(),
+            PoisonStateInner::Restored(record) => f
+                .debug_struct("PoisonState")
+                .field("restored", record)
+                .finish(),
+            PoisonStateInner::Frozen => f
+                .debug_struct("PoisonState")
+                .field("frozen", &true)
                 .finish(),
             PoisonStateInner::Unpoisoned => f.debug_struct("PoisonState").finish(),
         }
@@ -202,39 +1335,129 @@ impl fmt::Debug for PoisonStateInner {
 
 impl fmt::Display for PoisonStateInner {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The alternate format `{:#}` omits where the value was poisoned
+        let terse = f.alternate();
+
         match self {
             PoisonStateInner::CapturedPanic(panic) => {
-                write!(
-                    f,
-                    "poisoned by a panic '{}' (the poisoning guard was acquired at '{}')",
-                    panic.payload, panic.location
-                )
-            }
-            PoisonStateInner::UnknownPanic(panic) => write!(
-                f,
-                "poisoned by a panic (the poisoning guard was acquired at '{}')",
-                panic.location
-            ),
-            PoisonStateInner::CapturedErr(err) => write!(
-                f,
-                "poisoned by an error (the poisoning guard was acquired at '{}')",
-                err.location
-            ),
-            PoisonStateInner::UnknownErr(err) => write!(
-                f,
-                "poisoned by an error (the poisoning guard was acquired at '{}')",
-                err.location
-            ),
-            PoisonStateInner::Guarded(location) => write!(
-                f,
-                "poisoned (the poisoning guard was acquired at '{}')",
-                location
-            ),
+                write!(f, "poisoned by a panic '{}'", panic.payload)?;
+
+                if !terse {
+                    write!(
+                        f,
+                        " ({})",
+                        Origin {
+                            location: panic.location,
+                            thread: Some(&panic.thread),
+                        }
+                    )?;
+                }
+
+                Ok(())
+            }
+            PoisonStateInner::UnknownPanic(panic) => {
+                f.write_str("poisoned by a panic")?;
+
+                if !terse {
+                    if panic.converted {
+                        write!(
+                            f,
+                            " (converted from a poisoned lock at '{}')",
+                            panic.location
+                        )?;
+                    } else {
+                        write!(
+                            f,
+                            " ({})",
+                            Origin {
+                                location: panic.location,
+                                thread: panic.thread.as_ref(),
+                            }
+                        )?;
+                    }
+                }
+
+                Ok(())
+            }
+            PoisonStateInner::CapturedErr(err) => {
+                if let Some(ref context) = err.context {
+                    write!(f, "{}: ", context)?;
+                }
+
+                f.write_str("poisoned by an error")?;
+
+                if !terse {
+                    write!(
+                        f,
+                        " ({})",
+                        Origin {
+                            location: err.location,
+                            thread: Some(&err.thread),
+                        }
+                    )?;
+                }
+
+                Ok(())
+            }
+            PoisonStateInner::UnknownErr(err) => {
+                f.write_str("poisoned by an error")?;
+
+                if !terse {
+                    write!(
+                        f,
+                        " ({})",
+                        Origin {
+                            location: err.location,
+                            thread: Some(&err.thread),
+                        }
+                    )?;
+                }
+
+                Ok(())
+            }
+            PoisonStateInner::Guarded(location) | PoisonStateInner::Unrecovered(location) => {
+                fmt_guarded(location, f)
+            }
+            PoisonStateInner::Restored(record) => {
+                f.write_str(if record.panic {
+                    "poisoned by a panic"
+                } else {
+                    "poisoned by an error"
+                })?;
+
+                if let Some(ref message) = record.message {
+                    write!(f, " '{}'", message)?;
+                }
+
+                if !terse {
+                    write!(
+                        f,
+                        " (the value was poisoned at '{}:{}:{}' and restored from a record)",
+                        record.file, record.line, record.column
+                    )?;
+                }
+
+                Ok(())
+            }
+            PoisonStateInner::GuardedUnlessForgotten(_) => write!(f, "a guard was not poisoned"),
+            PoisonStateInner::Frozen => write!(f, "the value is frozen and can't be modified"),
             PoisonStateInner::Unpoisoned => write!(f, "a guard was not poisoned"),
         }
     }
 }
 
+impl<'a> fmt::Display for Origin<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the poisoning guard was acquired at '{}'", self.location)?;
+
+        if let Some(name) = self.thread.and_then(|thread| thread.name.as_ref()) {
+            write!(f, " and poisoned on thread '{}'", name)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Error for PoisonStateInner {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         if let PoisonStateInner::CapturedErr(ref err) = self {
@@ -244,3 +1467,24 @@ impl Error for PoisonStateInner {
         }
     }
 }
+
+impl fmt::Debug for FailedRecovery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FailedRecovery")
+            .field("source", &self.source)
+            .field("previous", &self.previous)
+            .finish()
+    }
+}
+
+impl fmt::Display for FailedRecovery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.source, f)
+    }
+}
+
+impl Error for FailedRecovery {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.previous)
+    }
+}
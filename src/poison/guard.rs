@@ -1,14 +1,33 @@
 use crate::poison::PoisonError;
 use std::{
+    borrow::{
+        Borrow,
+        BorrowMut,
+        Cow,
+    },
+    cell::Cell,
     error::Error,
     fmt,
     marker,
+    mem::ManuallyDrop,
     ops,
-    panic::UnwindSafe,
+    panic::{
+        self,
+        Location,
+        UnwindSafe,
+    },
+    ptr,
     thread,
 };
 
-use super::Poison;
+use super::{
+    error::PoisonState,
+    Poison,
+    PoisonRecover,
+};
+
+#[cfg(feature = "metrics")]
+use super::metrics::HoldTimer;
 
 /**
 A guard for a valid value that will unpoison on drop.
@@ -18,6 +37,11 @@ where
     Target: ops::DerefMut<Target = Poison<T>>,
 {
     target: Target,
+    unless_recovered: bool,
+    reborrowed: bool,
+    assumed_consistent: bool,
+    #[cfg(feature = "metrics")]
+    timer: HoldTimer,
     _marker: marker::PhantomData<&'a mut T>,
 }
 
@@ -30,22 +54,374 @@ impl<'a, T, Target> PoisonGuard<'a, T, Target>
 where
     Target: ops::DerefMut<Target = Poison<T>>,
 {
+    /**
+    Poison the value and return a recovery guard for it.
+
+    This method can be used when the value is no longer considered valid, but the decision to
+    recover it should be left to the caller.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+
+    let mut v = Poison::new(42);
+
+    let guard = Poison::on_unwind(&mut v).unwrap();
+
+    let recover = PoisonGuard::downgrade_to_recover(guard);
+
+    let guard = recover.recover_with(|v| *v = 0);
+
+    assert_eq!(0, *guard);
+    ```
+    */
+    #[track_caller]
+    pub fn downgrade_to_recover(mut guard: Self) -> PoisonRecover<'a, T, Target> {
+        guard.target.state.poison_with_error(None);
+
+        PoisonGuard::into_recover(guard)
+    }
+
+    /**
+    Get the location the guard was acquired at.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+
+    let mut v = Poison::new(42);
+
+    let guard = Poison::on_unwind(&mut v).unwrap();
+
+    println!("entered critical section at {}", PoisonGuard::acquired_at(&guard));
+    ```
+    */
+    pub fn acquired_at(guard: &Self) -> &'static Location<'static> {
+        // Values poisoned while a guard is held keep the location the guard was acquired at
+        guard
+            .target
+            .state
+            .location()
+            .expect("a guard is always acquired at a location")
+    }
+
+    /**
+    Run a fallible operation on the value, poisoning it if the operation fails.
+
+    Unlike [`Poison::try_recover`], this method doesn't consume the guard, so it can be used
+    for multiple operations within the same critical section. If the operation fails then the
+    value will remain poisoned, even after the guard is dropped.
+
+    ## Examples
+
+    ```
+    # fn some_fallible_operation(_: &mut i32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> { Ok(()) }
+    # fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use poison_guard::{Poison, PoisonGuard};
+
+    let mut v = Poison::new(42);
+
+    let mut guard = Poison::on_unwind(&mut v)?;
+
+    PoisonGuard::try_with(&mut guard, |v| some_fallible_operation(v))?;
+    PoisonGuard::try_with(&mut guard, |v| some_fallible_operation(v))?;
+    # Ok(())
+    # }
+    ```
+    */
+    #[track_caller]
+    pub fn try_with<O, E>(
+        guard: &mut Self,
+        f: impl FnOnce(&mut T) -> Result<O, E>,
+    ) -> Result<O, PoisonError>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        match f(PoisonGuard::value_mut(guard)) {
+            Ok(ok) => Ok(ok),
+            Err(err) => {
+                guard.target.state.poison_with_error(Some(err.into()));
+
+                Err(guard.target.state.to_error())
+            }
+        }
+    }
+
+    /**
+    Run an operation on the value that may fail or unwind, returning the guard if it succeeds.
+
+    If the operation returns an error or panics then the value is poisoned with it, and a
+    recovery guard for the value is returned instead. Panics are caught, so their message can
+    be captured in the poisoned value.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+    use std::io;
+
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    let guard = Poison::on_unwind(&mut v).unwrap();
+
+    let (len, guard) = PoisonGuard::guarded_catch_unwind(guard, |v| {
+        v.push(4);
+
+        Ok::<_, io::Error>(v.len())
+    })
+    .unwrap();
+
+    assert_eq!(4, len);
+    assert_eq!(4, guard.len());
+    ```
+    */
+    #[track_caller]
+    pub fn guarded_catch_unwind<R, E>(
+        mut guard: Self,
+        f: impl FnOnce(&mut T) -> Result<R, E>,
+    ) -> Result<(R, Self), PoisonRecover<'a, T, Target>>
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let value = PoisonGuard::value_mut(&mut guard);
+
+        match panic::catch_unwind(panic::AssertUnwindSafe(move || f(value))) {
+            Ok(Ok(r)) => Ok((r, guard)),
+            Ok(Err(err)) => {
+                guard.target.state.poison_with_error(Some(err.into()));
+
+                Err(PoisonGuard::into_recover(guard))
+            }
+            Err(panic) => {
+                guard.target.state.poison_with_panic(Some(panic));
+
+                Err(PoisonGuard::into_recover(guard))
+            }
+        }
+    }
+
+    /**
+    Run an operation on the value that may panic, but won't leave it in an invalid state.
+
+    If the operation panics then the unwind continues, but the guard won't poison the value when
+    the unwind passes through it. This can be used for code that's known to leave the value
+    consistent, like assertions in a read-only computation. Panics are still propagated, so
+    this method doesn't return if the operation panics.
+
+    If the panic is caught before the guard is dropped then the guard will poison on unwind as
+    normal again once the value is accessed mutably through it. Guards acquired through
+    [`Poison::unless_recovered`] still poison if they're dropped without being recovered.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+    use std::panic;
+
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let mut guard = Poison::on_unwind(&mut v).unwrap();
+
+        PoisonGuard::assume_consistent(&mut guard, |v| {
+            assert!(v.len() > 3, "not enough values");
+        });
+    }));
+
+    assert!(!v.is_poisoned());
+    ```
+    */
+    pub fn assume_consistent<R>(guard: &mut Self, f: impl FnOnce(&mut T) -> R) -> R {
+        let value = PoisonGuard::value_mut(guard);
+
+        match panic::catch_unwind(panic::AssertUnwindSafe(move || f(value))) {
+            Ok(r) => r,
+            Err(panic) => {
+                guard.assumed_consistent = true;
+
+                panic::resume_unwind(panic)
+            }
+        }
+    }
+
+    /**
+    Run a function on the value when the guard is released without panicking.
+
+    The function runs when the returned guard is dropped, before the value is unpoisoned. It
+    isn't run if a panic unwinds through the guard, so it can be used like a `finally` block
+    that only runs on success. If the function itself panics then the value is poisoned.
+
+    Guards acquired through [`Poison::unless_recovered`] must be released through
+    [`ReleasingGuard::recover`], which runs the function before recovering the guard. Dropping
+    them poisons the value without running the function.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+
+    let mut v = Poison::new(vec![3, 1, 2]);
+
+    let mut guard = PoisonGuard::on_release(Poison::on_unwind(&mut v).unwrap(), |v| v.sort());
+
+    guard.push(0);
+    drop(guard);
+
+    assert_eq!(&[0, 1, 2, 3], &**v.get().unwrap());
+    ```
+    */
+    pub fn on_release<F>(guard: Self, f: F) -> ReleasingGuard<'a, T, F, Target>
+    where
+        F: FnOnce(&mut T),
+    {
+        ReleasingGuard {
+            guard,
+            on_release: Some(f),
+        }
+    }
+
+    /**
+    Reborrow the guard for a shorter lifetime.
+
+    The reborrowed guard can be passed to functions that expect a guard without giving up this
+    one. It will poison the value if a panic unwinds through it, but dropping or recovering it
+    doesn't unpoison the value, so this guard stays responsible for the critical section. The
+    same is true of guards recovered from a reborrowed guard that was poisoned, like through
+    [`PoisonGuard::downgrade_to_recover`].
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+
+    fn push_all(mut guard: PoisonGuard<Vec<i32>>, values: &[i32]) {
+        guard.extend_from_slice(values);
+    }
+
+    let mut v = Poison::new(Vec::new());
+
+    let mut guard = Poison::on_unwind(&mut v).unwrap();
+
+    push_all(PoisonGuard::reborrow(&mut guard), &[1, 2]);
+    push_all(PoisonGuard::reborrow(&mut guard), &[3]);
+
+    assert_eq!(&[1, 2, 3], &**guard);
+    ```
+    */
+    #[track_caller]
+    pub fn reborrow(guard: &mut Self) -> PoisonGuard<'_, T> {
+        // Changes made through the reborrowed guard aren't known to be consistent
+        guard.assumed_consistent = false;
+
+        PoisonGuard {
+            target: &mut *guard.target,
+            // Recovering a recovery guard for the reborrow restores the state of this guard
+            unless_recovered: guard.unless_recovered,
+            reborrowed: true,
+            assumed_consistent: false,
+            // The guard this is reborrowed from reports how long the value was held for
+            #[cfg(feature = "metrics")]
+            timer: HoldTimer::disabled(),
+            _marker: Default::default(),
+        }
+    }
+
+    /**
+    Run a function with the target the guard was acquired from, such as a lock guard.
+
+    The target is only borrowed, because moving it out of the guard would release the lock
+    while the value is still guarded.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+    use parking_lot::{Mutex, MutexGuard};
+
+    let mutex = Mutex::new(Poison::new(42));
+
+    let guard = Poison::on_unwind(mutex.lock()).unwrap();
+
+    let locked = PoisonGuard::with_target(&guard, |lock| MutexGuard::mutex(lock).is_locked());
+
+    assert!(locked);
+    ```
+    */
+    pub fn with_target<R>(guard: &Self, f: impl FnOnce(&Target) -> R) -> R {
+        f(&guard.target)
+    }
+
     #[track_caller]
     pub(super) fn poison_on_unwind(mut target: Target) -> PoisonGuard<'a, T, Target> {
         target.state.guarded();
 
         PoisonGuard {
             target,
+            unless_recovered: false,
+            reborrowed: false,
+            assumed_consistent: false,
+            #[cfg(feature = "metrics")]
+            timer: HoldTimer::start(),
+            _marker: Default::default(),
+        }
+    }
+
+    #[track_caller]
+    pub(super) fn poison_on_unwind_unless_forgotten(
+        mut target: Target,
+    ) -> PoisonGuard<'a, T, Target> {
+        target.state.guarded_unless_forgotten();
+
+        PoisonGuard {
+            target,
+            unless_recovered: false,
+            reborrowed: false,
+            assumed_consistent: false,
+            #[cfg(feature = "metrics")]
+            timer: HoldTimer::start(),
             _marker: Default::default(),
         }
     }
 
     #[track_caller]
     pub(super) fn poison_now(mut target: Target) -> PoisonGuard<'a, T, Target> {
-        target.state.poison_with_error(None);
+        target.state.guarded_unless_recovered();
+
+        PoisonGuard {
+            target,
+            unless_recovered: true,
+            reborrowed: false,
+            assumed_consistent: false,
+            #[cfg(feature = "metrics")]
+            timer: HoldTimer::start(),
+            _marker: Default::default(),
+        }
+    }
+
+    #[track_caller]
+    pub(super) fn recover_reborrowed(
+        mut target: Target,
+        unless_recovered: bool,
+    ) -> PoisonGuard<'a, T, Target> {
+        // The value was poisoned at the location the guard it was reborrowed from was acquired
+        // Put it back into the state that guard expects, so it's still responsible for the value
+        let location = target.state.location().unwrap_or_else(Location::caller);
+
+        if unless_recovered {
+            target.state.guarded_unless_recovered_at(location);
+        } else {
+            target.state.guarded_at(location);
+        }
 
         PoisonGuard {
             target,
+            unless_recovered,
+            reborrowed: true,
+            assumed_consistent: false,
+            #[cfg(feature = "metrics")]
+            timer: HoldTimer::disabled(),
             _marker: Default::default(),
         }
     }
@@ -59,23 +435,308 @@ where
         guard.target.state.to_error()
     }
 
+    #[track_caller]
+    pub(super) fn poison_with_error_context<E>(
+        mut guard: Self,
+        e: E,
+        context: Cow<'static, str>,
+    ) -> PoisonError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        guard
+            .target
+            .state
+            .poison_with_error_context(e.into(), context);
+        guard.target.state.to_error()
+    }
+
+    #[track_caller]
+    pub(super) fn poison_with_error_code<E>(mut guard: Self, e: E, code: u32) -> PoisonError
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        guard.target.state.poison_with_error_code(e.into(), code);
+        guard.target.state.to_error()
+    }
+
     #[track_caller]
     pub(super) fn unpoison_now(mut guard: Self) {
-        guard.target.state.unpoison();
+        // The guard this was reborrowed from is responsible for unpoisoning
+        if !guard.reborrowed {
+            if guard.unless_recovered {
+                guard.target.state.unpoison_if_unrecovered();
+            }
+
+            guard.target.state.unpoison();
+        }
+    }
+
+    fn value_mut(guard: &mut Self) -> &mut T {
+        // Any changes made from here on aren't known to be consistent
+        guard.assumed_consistent = false;
+
+        &mut guard.target.value
+    }
+
+    fn into_recover(guard: Self) -> PoisonRecover<'a, T, Target> {
+        let reborrowed = guard.reborrowed;
+
+        let recover = if guard.unless_recovered {
+            PoisonRecover::recover_to_poison_now(PoisonGuard::into_target(guard))
+        } else {
+            PoisonRecover::recover_to_poison_on_unwind(PoisonGuard::into_target(guard))
+        };
+
+        if reborrowed {
+            recover.into_reborrowed()
+        } else {
+            recover
+        }
+    }
+
+    fn into_target(guard: Self) -> Target {
+        let guard = ManuallyDrop::new(guard);
+
+        // The guard won't be dropped, so report how long it was held for here
+        #[cfg(feature = "metrics")]
+        guard.timer.stop();
+
+        // SAFETY: The guard is never dropped, so the target is only read once
+        unsafe { ptr::read(&guard.target) }
     }
 }
 
-impl<'a, T, Target> Drop for PoisonGuard<'a, T, Target>
+impl<'a, A, B, Target> PoisonGuard<'a, (A, B), Target>
+where
+    Target: ops::DerefMut<Target = Poison<(A, B)>>,
+{
+    /**
+    Split a guard for a pair into guards for each half.
+
+    The halves can be used independently, but share the poison state of the pair. If a panic
+    unwinds through either half then the whole pair is poisoned. The halves borrow the guard,
+    so it stays responsible for poisoning the pair when it's dropped.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard};
+
+    let mut v = Poison::new((vec![1, 2, 3], String::from("a")));
+
+    let mut guard = Poison::on_unwind(&mut v).unwrap();
+
+    let (mut numbers, mut letters) = PoisonGuard::split(&mut guard);
+
+    numbers.push(4);
+    letters.push('b');
+
+    drop((numbers, letters));
+
+    assert_eq!(4, guard.0.len());
+    assert_eq!("ab", guard.1);
+    ```
+    */
+    pub fn split(guard: &mut Self) -> (PoisonGuardPart<'_, A>, PoisonGuardPart<'_, B>) {
+        guard.assumed_consistent = false;
+
+        let Poison {
+            value: (a, b),
+            state,
+        } = &mut *guard.target;
+
+        let state = Cell::from_mut(state);
+
+        (
+            PoisonGuardPart { value: a, state },
+            PoisonGuardPart { value: b, state },
+        )
+    }
+}
+
+/**
+A guard for part of a valid value that will poison the whole value if a panic unwinds through it.
+
+Parts are created by [`PoisonGuard::split`].
+*/
+pub struct PoisonGuardPart<'g, T> {
+    value: &'g mut T,
+    state: &'g Cell<PoisonState>,
+}
+
+/**
+A guard for a valid value that runs a function when it's released without panicking.
+
+Releasing guards are created by [`PoisonGuard::on_release`].
+*/
+pub struct ReleasingGuard<'a, T, F, Target = &'a mut Poison<T>>
 where
+    F: FnOnce(&mut T),
     Target: ops::DerefMut<Target = Poison<T>>,
 {
+    guard: PoisonGuard<'a, T, Target>,
+    on_release: Option<F>,
+}
+
+impl<'a, T, F, Target> ReleasingGuard<'a, T, F, Target>
+where
+    F: FnOnce(&mut T),
+    Target: ops::DerefMut<Target = Poison<T>> + 'a,
+{
+    /**
+    Run the function and recover the guard, unpoisoning it if it was poisoned.
+
+    This is the equivalent of [`Poison::recover`] for releasing guards, and must be used to
+    release guards acquired through [`Poison::unless_recovered`]. If the function panics then the
+    value is poisoned.
+
+    ## Examples
+
+    ```
+    use poison_guard::{Poison, PoisonGuard, ReleasingGuard};
+
+    let mut v = Poison::new(vec![3, 1, 2]);
+
+    let mut guard = PoisonGuard::on_release(Poison::unless_recovered(&mut v).unwrap(), |v| v.sort());
+
+    guard.push(0);
+    ReleasingGuard::recover(guard);
+
+    assert_eq!(&[0, 1, 2, 3], &**v.get().unwrap());
+    ```
+    */
+    pub fn recover(guard: Self) {
+        let mut guard = ManuallyDrop::new(guard);
+
+        let on_release = guard.on_release.take();
+
+        // SAFETY: The releasing guard is never dropped, so the inner guard is only read once
+        let mut guard = unsafe { ptr::read(&guard.guard) };
+
+        // If the function panics then the guard is dropped while unwinding, which poisons
+        if let Some(on_release) = on_release {
+            on_release(&mut guard);
+        }
+
+        Poison::recover(guard);
+    }
+}
+
+impl<'a, T, F, Target> Drop for ReleasingGuard<'a, T, F, Target>
+where
+    F: FnOnce(&mut T),
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    fn drop(&mut self) {
+        // The guard is dropped after this, so it will poison if the function panics
+        if !thread::panicking() && !self.guard.unless_recovered {
+            if let Some(on_release) = self.on_release.take() {
+                on_release(&mut self.guard);
+            }
+        }
+    }
+}
+
+impl<'a, T, F, Target> fmt::Debug for ReleasingGuard<'a, T, F, Target>
+where
+    T: fmt::Debug,
+    F: FnOnce(&mut T),
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReleasingGuard")
+            .field("value", &*self.guard)
+            .finish()
+    }
+}
+
+impl<'a, T, F, Target> ops::Deref for ReleasingGuard<'a, T, F, Target>
+where
+    F: FnOnce(&mut T),
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T, F, Target> ops::DerefMut for ReleasingGuard<'a, T, F, Target>
+where
+    F: FnOnce(&mut T),
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+impl<'g, T> Drop for PoisonGuardPart<'g, T> {
     #[track_caller]
     fn drop(&mut self) {
         if thread::panicking() {
+            let mut state = self.state.take();
+            state.poison_with_panic(None);
+            self.state.set(state);
+        }
+    }
+}
+
+impl<'g, T> fmt::Debug for PoisonGuardPart<'g, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PoisonGuardPart")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<'g, T> fmt::Display for PoisonGuardPart<'g, T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&*self.value, f)
+    }
+}
+
+impl<'g, T> ops::Deref for PoisonGuardPart<'g, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'g, T> ops::DerefMut for PoisonGuardPart<'g, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value
+    }
+}
+
+impl<'a, T, Target> Drop for PoisonGuard<'a, T, Target>
+where
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    #[track_caller]
+    fn drop(&mut self) {
+        if thread::panicking() && !self.assumed_consistent {
             self.target.state.poison_with_panic(None);
+        } else if self.reborrowed {
+            // The guard this was reborrowed from is responsible for unpoisoning
+        } else if self.unless_recovered {
+            // The guard wasn't explicitly recovered, so the value stays poisoned
+            self.target.state.poison_if_unrecovered();
         } else {
             self.target.state.unpoison_if_guarded();
         }
+
+        #[cfg(feature = "metrics")]
+        self.timer.stop();
     }
 }
 
@@ -86,11 +747,21 @@ where
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("PoisonGuard")
-            .field(&"value", &**self)
+            .field("value", &**self)
             .finish()
     }
 }
 
+impl<'a, T, Target> fmt::Display for PoisonGuard<'a, T, Target>
+where
+    T: fmt::Display,
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
 impl<'a, T, Target> ops::Deref for PoisonGuard<'a, T, Target>
 where
     Target: ops::DerefMut<Target = Poison<T>>,
@@ -107,6 +778,24 @@ where
     Target: ops::DerefMut<Target = Poison<T>>,
 {
     fn deref_mut(&mut self) -> &mut T {
-        &mut self.target.value
+        PoisonGuard::value_mut(self)
+    }
+}
+
+impl<'a, T, Target> Borrow<T> for PoisonGuard<'a, T, Target>
+where
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    fn borrow(&self) -> &T {
+        &self.target.value
+    }
+}
+
+impl<'a, T, Target> BorrowMut<T> for PoisonGuard<'a, T, Target>
+where
+    Target: ops::DerefMut<Target = Poison<T>>,
+{
+    fn borrow_mut(&mut self) -> &mut T {
+        PoisonGuard::value_mut(self)
     }
 }
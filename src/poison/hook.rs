@@ -0,0 +1,66 @@
+use std::sync::{
+    atomic::{
+        AtomicBool,
+        Ordering,
+    },
+    RwLock,
+};
+
+use super::PoisonError;
+
+static HOOK: RwLock<Option<fn(&PoisonError)>> = RwLock::new(None);
+static HAS_HOOK: AtomicBool = AtomicBool::new(false);
+
+/**
+Register a hook that's called whenever any `Poison<T>` becomes poisoned.
+
+The hook replaces any previously registered one. It's called on the thread that poisoned the
+value, which may be in the middle of unwinding from a panic. If the hook itself panics while
+the thread is unwinding then the process will abort.
+
+## Examples
+
+```
+use poison_guard::{Poison, PoisonError};
+
+fn report(err: &PoisonError) {
+    eprintln!("{}", err);
+}
+
+poison_guard::set_poison_hook(report);
+
+let mut v = Poison::new(42);
+
+// Dropping this guard without recovering it will call the hook
+drop(Poison::unless_recovered(&mut v).unwrap());
+# poison_guard::take_poison_hook();
+```
+*/
+pub fn set_poison_hook(hook: fn(&PoisonError)) {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = Some(hook);
+    HAS_HOOK.store(true, Ordering::Release);
+}
+
+/**
+Unregister the hook set by [`set_poison_hook`], returning it.
+*/
+pub fn take_poison_hook() -> Option<fn(&PoisonError)> {
+    let mut hook = HOOK.write().unwrap_or_else(|err| err.into_inner());
+    HAS_HOOK.store(false, Ordering::Release);
+
+    hook.take()
+}
+
+pub(super) fn poisoned(err: impl FnOnce() -> PoisonError) {
+    // Avoid touching the lock at all if there's no hook to call
+    if !HAS_HOOK.load(Ordering::Acquire) {
+        return;
+    }
+
+    // Copy the hook out so it can call `set_poison_hook` without deadlocking
+    let hook = *HOOK.read().unwrap_or_else(|err| err.into_inner());
+
+    if let Some(hook) = hook {
+        hook(&err());
+    }
+}
@@ -0,0 +1,130 @@
+/*!
+Poisoning for values that aren't shared across threads.
+*/
+
+use std::{
+    error::Error,
+    fmt,
+    marker,
+    ops,
+};
+
+use super::{
+    Poison,
+    PoisonError,
+};
+
+/**
+A container that holds a potentially poisoned value, and can be poisoned with errors that aren't
+`Send` or `Sync`.
+
+`LocalPoison<T>` dereferences to a [`Poison<T>`], so guards can be acquired for it in the same
+way. The difference is that [`LocalPoison::poison_with`] accepts errors that can't be sent across
+threads, like those holding an `Rc`. Because of this, `LocalPoison<T>` itself can't be shared
+across threads.
+
+## Examples
+
+```
+use poison_guard::local::LocalPoison;
+use std::{fmt, error::Error, rc::Rc};
+
+#[derive(Debug)]
+struct LocalError(Rc<str>);
+
+impl fmt::Display for LocalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for LocalError {}
+
+let mut v = LocalPoison::new(42);
+
+v.poison_with(LocalError(Rc::from("the value is no longer valid"))).unwrap();
+
+assert!(v.is_poisoned());
+assert!(v.local_error().unwrap().is::<LocalError>());
+```
+*/
+pub struct LocalPoison<T> {
+    poison: Poison<T>,
+    // The reason the inner value was poisoned with, used to tell if it's been poisoned again since
+    err: Option<(PoisonError, Box<dyn Error>)>,
+    _marker: marker::PhantomData<*const ()>,
+}
+
+impl<T> LocalPoison<T> {
+    /**
+    Create a new `LocalPoison<T>` with a valid inner value.
+    */
+    pub fn new(v: T) -> Self {
+        LocalPoison {
+            poison: Poison::new(v),
+            err: None,
+            _marker: Default::default(),
+        }
+    }
+
+    /**
+    Poison the value with the given error.
+
+    The error is kept alongside the value, and can be retrieved through
+    [`LocalPoison::local_error`] until the value is recovered. Errors returned through guards
+    for the value will include the error's message, but not the error itself.
+
+    This method will return `Err` if the value is frozen, like [`Poison::poison_with`].
+    */
+    #[track_caller]
+    pub fn poison_with<E>(&mut self, e: E) -> Result<(), PoisonError>
+    where
+        E: Into<Box<dyn Error>>,
+    {
+        let e = e.into();
+
+        self.poison.poison_with(LocalError(e.to_string()))?;
+        self.err = Some((self.poison.state.to_error(), e));
+
+        Ok(())
+    }
+
+    /**
+    Get the error the value was poisoned with through [`LocalPoison::poison_with`].
+
+    This method will return `None` if the value isn't poisoned, or if the reason it was poisoned
+    has been replaced since [`LocalPoison::poison_with`] was called, like by poisoning the inner
+    value again or through [`Poison::map_poison`].
+    */
+    pub fn local_error(&self) -> Option<&(dyn Error + 'static)> {
+        match self.err {
+            Some((ref reason, ref err)) if self.poison.state.is_poisoned_by(reason) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+impl<T> ops::Deref for LocalPoison<T> {
+    type Target = Poison<T>;
+
+    fn deref(&self) -> &Poison<T> {
+        &self.poison
+    }
+}
+
+impl<T> ops::DerefMut for LocalPoison<T> {
+    fn deref_mut(&mut self) -> &mut Poison<T> {
+        &mut self.poison
+    }
+}
+
+#[derive(Debug)]
+struct LocalError(String);
+
+impl fmt::Display for LocalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for LocalError {}
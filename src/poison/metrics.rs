@@ -0,0 +1,161 @@
+/*!
+Instrumentation for how long poisoning guards are held.
+
+This module is only available with the `metrics` feature.
+*/
+
+use std::{
+    panic::Location,
+    sync::{
+        atomic::{
+            AtomicBool,
+            Ordering,
+        },
+        RwLock,
+    },
+    time::{
+        Duration,
+        Instant,
+    },
+};
+
+type HoldHook = fn(&'static Location<'static>, Duration);
+
+static HOOK: RwLock<Option<HoldHook>> = RwLock::new(None);
+static HAS_HOOK: AtomicBool = AtomicBool::new(false);
+
+/**
+Register a hook that's called with the time each poisoning guard was held for.
+
+The hook is called when the guard is dropped with the location the guard was acquired at.
+Guards acquired while no hook is registered aren't timed. The hook replaces any previously
+registered one. It's called on the thread that dropped the guard, which may be in the middle of
+unwinding from a panic. If the hook itself panics while the thread is unwinding then the process
+will abort.
+
+## Examples
+
+```
+use poison_guard::Poison;
+use std::{panic::Location, time::Duration};
+
+fn report(acquired_at: &'static Location<'static>, held_for: Duration) {
+    eprintln!("guard acquired at {} was held for {:?}", acquired_at, held_for);
+}
+
+poison_guard::metrics::set_hold_hook(report);
+
+let mut v = Poison::new(42);
+
+// Dropping this guard will call the hook
+drop(Poison::on_unwind(&mut v).unwrap());
+# poison_guard::metrics::take_hold_hook();
+```
+*/
+pub fn set_hold_hook(hook: fn(&'static Location<'static>, Duration)) {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = Some(hook);
+    HAS_HOOK.store(true, Ordering::Release);
+}
+
+/**
+Unregister the hook set by [`set_hold_hook`], returning it.
+*/
+pub fn take_hold_hook() -> Option<HoldHook> {
+    let mut hook = HOOK.write().unwrap_or_else(|err| err.into_inner());
+    HAS_HOOK.store(false, Ordering::Release);
+
+    hook.take()
+}
+
+/**
+The point a guard was acquired, used to report how long it was held for.
+*/
+pub(super) struct HoldTimer {
+    acquired: Option<(&'static Location<'static>, Instant)>,
+}
+
+impl HoldTimer {
+    #[inline]
+    #[track_caller]
+    pub(super) fn start() -> Self {
+        // Avoid reading the clock at all if there's no hook to report to
+        if !HAS_HOOK.load(Ordering::Acquire) {
+            return HoldTimer { acquired: None };
+        }
+
+        HoldTimer {
+            acquired: Some((Location::caller(), clock::now())),
+        }
+    }
+
+    /**
+    A timer that never reports, for guards that aren't responsible for the value.
+    */
+    #[inline]
+    pub(super) fn disabled() -> Self {
+        HoldTimer { acquired: None }
+    }
+
+    #[inline]
+    pub(super) fn stop(&self) {
+        if let Some((location, acquired)) = self.acquired {
+            let held_for = clock::now().saturating_duration_since(acquired);
+
+            // Copy the hook out so it can call `set_hold_hook` without deadlocking
+            let hook = *HOOK.read().unwrap_or_else(|err| err.into_inner());
+
+            if let Some(hook) = hook {
+                hook(location, held_for);
+            }
+        }
+    }
+}
+
+#[cfg(not(test))]
+mod clock {
+    use std::time::Instant;
+
+    pub(super) fn now() -> Instant {
+        Instant::now()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod clock {
+    use std::{
+        cell::Cell,
+        time::{
+            Duration,
+            Instant,
+        },
+    };
+
+    thread_local! {
+        static NOW: Cell<Option<Instant>> = const { Cell::new(None) };
+    }
+
+    pub(crate) fn now() -> Instant {
+        NOW.with(|now| now.get()).unwrap_or_else(Instant::now)
+    }
+
+    /**
+    Freeze the clock for the current thread.
+    */
+    pub(crate) fn freeze() {
+        NOW.with(|now| now.set(Some(Instant::now())));
+    }
+
+    /**
+    Move the frozen clock for the current thread forwards.
+    */
+    pub(crate) fn advance(by: Duration) {
+        NOW.with(|now| now.set(Some(now.get().expect("the clock isn't frozen") + by)));
+    }
+
+    /**
+    Unfreeze the clock for the current thread.
+    */
+    pub(crate) fn unfreeze() {
+        NOW.with(|now| now.set(None));
+    }
+}
@@ -14,10 +14,17 @@ use super::{
 
 /**
 A guard for a poisoned value.
+
+Guards are also returned for frozen values, which can't be recovered. Check
+[`PoisonRecover::is_frozen`] before recovering a value that may be frozen.
+[`PoisonRecover::try_recover_with`] and [`PoisonRecover::recover_or_propagate`] return `Err`
+for frozen values, and the other methods for recovering a value will panic.
 */
 pub struct PoisonRecover<'a, T, Target = &'a mut Poison<T>> {
     target: Target,
     recover_to_poison_now: bool,
+    // Recovered from a reborrowed guard, so recovering produces a reborrowed guard too
+    reborrowed: bool,
     _marker: marker::PhantomData<&'a mut T>,
 }
 
@@ -38,6 +45,12 @@ where
     */
     #[track_caller]
     pub fn recover(self) -> PoisonGuard<'a, T, Target> {
+        self.assert_not_frozen();
+
+        if self.reborrowed {
+            return self.into_guard();
+        }
+
         PoisonGuard::poison_on_unwind(self.target)
     }
 
@@ -48,12 +61,126 @@ where
     */
     #[track_caller]
     pub fn recover_with(mut self, f: impl FnOnce(&mut T)) -> PoisonGuard<'a, T, Target> {
-        f(&mut self.target.value);
+        f(self.value_mut());
 
-        if self.recover_to_poison_now {
-            PoisonGuard::poison_now(self.target)
+        self.into_guard()
+    }
+
+    /**
+    Recover a poisoned value by resetting it to its default.
+
+    After this call, any future accesses to the value will succeed.
+    */
+    #[track_caller]
+    pub fn recover_or_default(self) -> PoisonGuard<'a, T, Target>
+    where
+        T: Default,
+    {
+        self.recover_with(|v| *v = Default::default())
+    }
+
+    /**
+    Recover a poisoned value by replacing it with a new one.
+
+    This is useful for values that can't be repaired in-place, like a broken connection
+    that needs to be reopened.
+
+    After this call, any future accesses to the value will succeed.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    drop(Poison::unless_recovered(&mut v).unwrap());
+
+    let guard = Poison::on_unwind(&mut v).unwrap_err().recover_replace(Vec::new());
+
+    assert!(guard.is_empty());
+    ```
+    */
+    #[track_caller]
+    pub fn recover_replace(self, value: T) -> PoisonGuard<'a, T, Target> {
+        self.recover_with(|v| *v = value)
+    }
+
+    /**
+    Recover a poisoned value by making a new one with the given closure and replacing it.
+
+    This is like [`PoisonRecover::recover_replace`], but the new value is only made once the
+    value is being recovered. If `make` panics then the old value is left in place and the value
+    remains poisoned.
+
+    After this call, any future accesses to the value will succeed.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+
+    let mut v = Poison::new(String::from("a broken connection"));
+
+    drop(Poison::unless_recovered(&mut v).unwrap());
+
+    let guard = Poison::on_unwind(&mut v)
+        .unwrap_err()
+        .recover_with_factory(|| String::from("a fresh connection"));
+
+    assert_eq!("a fresh connection", *guard);
+    ```
+    */
+    #[track_caller]
+    pub fn recover_with_factory(self, make: impl FnOnce() -> T) -> PoisonGuard<'a, T, Target> {
+        let value = make();
+
+        self.recover_replace(value)
+    }
+
+    /**
+    Recover a poisoned value with the given closure if a policy allows it, or return the
+    reason it was poisoned.
+
+    The `policy` is called with the reason the value was poisoned. If it returns `true` then
+    the value is recovered with `recover`. If it returns `false` then the value stays poisoned
+    and the error is returned so it can be propagated. If the value is frozen then `policy`
+    isn't called, and an error that reports [`PoisonError::is_frozen`] is returned.
+
+    ## Examples
+
+    Recovering errors but propagating panics:
+
+    ```
+    use poison_guard::{Poison, PoisonError};
+
+    # fn main() -> Result<(), PoisonError> {
+    let mut v = Poison::new(vec![1, 2, 3]);
+
+    drop(Poison::unless_recovered(&mut v).unwrap());
+
+    let guard = match Poison::on_unwind(&mut v) {
+        Ok(guard) => guard,
+        Err(recover) => recover.recover_or_propagate(PoisonError::is_recoverable, |v| v.clear())?,
+    };
+
+    assert!(guard.is_empty());
+    # Ok(())
+    # }
+    ```
+    */
+    #[track_caller]
+    pub fn recover_or_propagate(
+        self,
+        policy: impl FnOnce(&PoisonError) -> bool,
+        recover: impl FnOnce(&mut T),
+    ) -> Result<PoisonGuard<'a, T, Target>, PoisonError> {
+        let err = self.target.state.to_error();
+
+        if !self.is_frozen() && policy(&err) {
+            Ok(self.recover_with(recover))
         } else {
-            PoisonGuard::poison_on_unwind(self.target)
+            Err(err)
         }
     }
 
@@ -61,6 +188,10 @@ where
     Try recover a poisoned value with the given closure.
 
     If this call succeeds, any future accesses to the value will succeed.
+    If this call fails, the value will remain poisoned with the returned error.
+    The original reason the value was poisoned is kept as the source of that error.
+
+    If the value is frozen then `f` isn't called, and the guard is returned as an error.
     */
     #[track_caller]
     pub fn try_recover_with<E>(
@@ -70,55 +201,105 @@ where
     where
         E: Into<Box<dyn Error + Send + Sync>>,
     {
-        match f(&mut self.target.value) {
+        if self.is_frozen() {
+            return Err(self);
+        }
+
+        match f(self.value_mut()) {
             // The guard was recovered, return it
             Ok(()) => {
-                if self.recover_to_poison_now {
-                    Ok(PoisonGuard::poison_now(self.target))
-                } else {
+                if !self.reborrowed && !self.recover_to_poison_now {
                     self.target.state.unpoison_if_guarded();
-
-                    Ok(PoisonGuard::poison_on_unwind(self.target))
                 }
+
+                Ok(self.into_guard())
             }
             // The guard was not recovered, we set it to an errored state
             // If the guard was previously poisoned for a different reason
-            // this will replace it
+            // then that reason is kept as the source of the new error
             Err(e) => {
-                self.target.state.poison_with_error(Some(e.into()));
+                self.target.state.poison_with_failed_recovery(e.into());
 
                 Err(self)
             }
         }
     }
 
+    /**
+    Whether or not the value is frozen.
+
+    Frozen values can't be recovered. See [`Poison::freeze`] for more details.
+    */
+    pub fn is_frozen(&self) -> bool {
+        self.target.state.is_frozen()
+    }
+
     /**
     Convert this recovery guard into an error.
     */
     pub fn into_error(self) -> PoisonError {
         self.into()
     }
+
+    #[track_caller]
+    fn into_guard(self) -> PoisonGuard<'a, T, Target> {
+        if self.reborrowed {
+            PoisonGuard::recover_reborrowed(self.target, self.recover_to_poison_now)
+        } else if self.recover_to_poison_now {
+            PoisonGuard::poison_now(self.target)
+        } else {
+            PoisonGuard::poison_on_unwind(self.target)
+        }
+    }
+
+    #[track_caller]
+    fn value_mut(&mut self) -> &mut T {
+        self.assert_not_frozen();
+
+        &mut self.target.value
+    }
+
+    #[track_caller]
+    fn assert_not_frozen(&self) {
+        assert!(
+            !self.is_frozen(),
+            "attempt to recover a frozen value; check `PoisonRecover::is_frozen` first"
+        );
+    }
 }
 
 impl<'a, T, Target> PoisonRecover<'a, T, Target>
 where
     Target: ops::Deref<Target = Poison<T>>,
 {
+    // Recovery guards are only constructed when the value is poisoned, so keep them out of
+    // the unpoisoned path of methods like `Poison::on_unwind`.
+    #[cold]
+    #[inline(never)]
     pub(super) fn recover_to_poison_on_unwind(target: Target) -> PoisonRecover<'a, T, Target> {
         PoisonRecover {
             target,
             recover_to_poison_now: false,
+            reborrowed: false,
             _marker: Default::default(),
         }
     }
 
+    #[cold]
+    #[inline(never)]
     pub(super) fn recover_to_poison_now(target: Target) -> PoisonRecover<'a, T, Target> {
         PoisonRecover {
             target,
             recover_to_poison_now: true,
+            reborrowed: false,
             _marker: Default::default(),
         }
     }
+
+    pub(super) fn into_reborrowed(mut self) -> PoisonRecover<'a, T, Target> {
+        self.reborrowed = true;
+        self
+    }
 }
 
 impl<'a, T, Target> fmt::Debug for PoisonRecover<'a, T, Target>
@@ -127,7 +308,7 @@ where
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("PoisonRecover")
-            .field(&"source", &self.target.state.as_dyn_error())
+            .field("source", &self.target.state.as_dyn_error())
             .finish()
     }
 }
@@ -0,0 +1,35 @@
+/*!
+Shared statistics for individual poisoned values.
+*/
+
+use std::sync::{
+    atomic::{
+        AtomicU64,
+        Ordering,
+    },
+    Arc,
+};
+
+/**
+A handle to the statistics of a `Poison<T>` created with [`Poison::with_stats`].
+
+The handle can be cloned and read from any thread without synchronizing with the value itself,
+so it can be used to report gauges while the value is locked elsewhere.
+
+[`Poison::with_stats`]: crate::Poison::with_stats
+*/
+#[derive(Debug, Clone, Default)]
+pub struct PoisonStats(Arc<AtomicU64>);
+
+impl PoisonStats {
+    /**
+    Get the number of times the value has been poisoned.
+    */
+    pub fn poisoned_count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn poisoned(&self) {
+        self.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,79 @@
+/*!
+Guards for values protected by a standard library `Mutex<Poison<T>>`.
+*/
+
+use std::sync::{
+    self,
+    LockResult,
+    MutexGuard,
+};
+
+use super::{
+    Poison,
+    PoisonGuard,
+    PoisonRecover,
+};
+
+/**
+A guard for a valid value in a standard library `Mutex`.
+*/
+pub type StdPoisonGuard<'a, T> = PoisonGuard<'a, T, MutexGuard<'a, Poison<T>>>;
+
+/**
+A guard for a poisoned value in a standard library `Mutex`.
+*/
+pub type StdPoisonRecover<'a, T> = PoisonRecover<'a, T, MutexGuard<'a, Poison<T>>>;
+
+impl<T> Poison<T> {
+    /**
+    Get a guard to a value in a standard library `Mutex` that will only poison if a panic
+    unwinds through the guard.
+
+    The standard library's `Mutex` implements its own poisoning, so a panic that unwinds through
+    a guard will poison both the `Mutex` and the `Poison<T>` inside it. This method accepts the
+    result of locking the `Mutex` and ignores the `Mutex`'s poisoning, so whether the value is
+    poisoned is only tracked by `Poison<T>` and can be recovered through it.
+
+    See [`Poison::on_unwind`] for more details.
+
+    ## Examples
+
+    ```
+    use poison_guard::Poison;
+    use std::{panic, sync::Mutex};
+
+    let mutex = Mutex::new(Poison::new(42));
+
+    let _ = panic::catch_unwind(|| {
+        let _guard = Poison::on_unwind_std(mutex.lock()).unwrap();
+
+        panic!("explicit panic");
+    });
+
+    // Both the `Mutex` and the `Poison<T>` are poisoned, but the value can be recovered
+    let guard = Poison::on_unwind_std(mutex.lock()).unwrap_err().recover();
+
+    assert_eq!(42, *guard);
+    ```
+    */
+    #[track_caller]
+    pub fn on_unwind_std(
+        lock: LockResult<MutexGuard<Poison<T>>>,
+    ) -> Result<StdPoisonGuard<T>, StdPoisonRecover<T>> {
+        Poison::on_unwind(lock.unwrap_or_else(sync::PoisonError::into_inner))
+    }
+
+    /**
+    Get a guard to a value in a standard library `Mutex` that will immediately poison and only
+    unpoison with [`Poison::recover`] or [`Poison::try_recover`].
+
+    The `Mutex`'s own poisoning is ignored, like [`Poison::on_unwind_std`]. See
+    [`Poison::unless_recovered`] for more details.
+    */
+    #[track_caller]
+    pub fn unless_recovered_std(
+        lock: LockResult<MutexGuard<Poison<T>>>,
+    ) -> Result<StdPoisonGuard<T>, StdPoisonRecover<T>> {
+        Poison::unless_recovered(lock.unwrap_or_else(sync::PoisonError::into_inner))
+    }
+}
@@ -0,0 +1,52 @@
+/*!
+Converting poisoned values into gRPC statuses.
+
+This module is only available with the `tonic` feature.
+*/
+
+use std::sync::Arc;
+
+use super::PoisonError;
+
+/**
+Convert a `PoisonError` into a `tonic::Status`.
+
+Poisoned values are converted into a status with the `Internal` code, because they mean the
+server's own state is no longer valid. Frozen values are converted into a status with the
+`FailedPrecondition` code instead.
+
+The status message is fixed, so panic payloads, error contexts, and the location the value was
+poisoned at aren't sent to clients. The original error is kept as the status' source, so it can
+still be logged on the server.
+
+## Examples
+
+```
+use poison_guard::{Poison, PoisonError};
+
+let mut v = Poison::new(42);
+
+let guard = Poison::unless_recovered(&mut v).unwrap();
+drop(guard);
+
+let err = PoisonError::from(Poison::on_unwind(&mut v).unwrap_err());
+let status = tonic::Status::from(err);
+
+assert_eq!(tonic::Code::Internal, status.code());
+assert_eq!("internal state poisoned by an error", status.message());
+```
+*/
+impl From<PoisonError> for tonic::Status {
+    fn from(err: PoisonError) -> Self {
+        let mut status = if err.is_frozen() {
+            tonic::Status::failed_precondition("internal state is frozen")
+        } else if err.is_captured_panic() || err.is_unknown_panic() {
+            tonic::Status::internal("internal state poisoned by a panic")
+        } else {
+            tonic::Status::internal("internal state poisoned by an error")
+        };
+
+        status.set_source(Arc::new(err));
+        status
+    }
+}
@@ -1,15 +1,199 @@
 use crate::{
-    poison::PoisonGuard,
+    poison::{
+        PoisonError,
+        PoisonGuard,
+        PoisonRecord,
+    },
     Poison,
 };
 use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
     error::Error,
+    fmt,
     io,
-    panic,
+    mem,
+    panic::{
+        self,
+        RefUnwindSafe,
+        UnwindSafe,
+    },
+    sync::{
+        atomic::{
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+    },
+    thread,
 };
 
+#[cfg(feature = "anyhow")]
+mod anyhow_error;
+#[cfg(feature = "parking_lot")]
+mod arc;
+mod futures_mutex;
+mod local;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod poison_on_unwind;
 mod poison_unless_recovered;
+mod std_mutex;
+#[cfg(feature = "tonic")]
+mod tonic_status;
+
+#[test]
+fn poison_size() {
+    assert!(mem::size_of::<Poison<()>>() <= mem::size_of::<usize>() + mem::size_of::<()>());
+}
+
+#[test]
+fn poison_guard_does_not_allocate() {
+    let mut poison = Poison::new(0);
+
+    drop(Poison::on_unwind(&mut poison).unwrap());
+    drop(Poison::on_unwind_no_forget_poison(&mut poison).unwrap());
+    Poison::recover(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(!poison.is_allocated());
+
+    // Forgotten guards keep their location without allocating
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+    let acquired_at = PoisonGuard::acquired_at(&guard).to_string();
+    mem::forget(guard);
+
+    assert!(poison.is_poisoned());
+    assert!(!poison.is_allocated());
+
+    let recover = Poison::on_unwind(&mut poison).unwrap_err();
+
+    assert!(recover.to_string().contains(&acquired_at));
+    assert!(AsRef::<dyn Error + Send + Sync>::as_ref(&recover)
+        .to_string()
+        .contains(&acquired_at));
+    assert!(PoisonError::from(recover).was_guard_drop());
+
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    assert!(!poison.is_poisoned());
+    assert!(!poison.is_allocated());
+}
+
+#[test]
+fn poison_failure_allocates() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(poison.is_allocated());
+
+    // The payload is kept after recovering, so the generation isn't lost
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    assert!(poison.is_allocated());
+    assert_eq!(1, poison.poison_generation());
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+fn poison_auto_traits() {
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+    fn assert_unpin<T: Unpin>() {}
+    fn assert_unwind_safe<T: UnwindSafe>() {}
+    fn assert_ref_unwind_safe<T: RefUnwindSafe>() {}
+
+    // Fails to compile if the type is `RefUnwindSafe`, because `AmbiguousIfImpl::check` would
+    // then have two candidate impls
+    macro_rules! assert_not_ref_unwind_safe {
+        ($ty:ty) => {{
+            trait AmbiguousIfImpl<A> {
+                fn check() {}
+            }
+
+            impl<T: ?Sized> AmbiguousIfImpl<()> for T {}
+
+            struct IsRefUnwindSafe;
+
+            impl<T: ?Sized + RefUnwindSafe> AmbiguousIfImpl<IsRefUnwindSafe> for T {}
+
+            <$ty as AmbiguousIfImpl<_>>::check();
+        }};
+    }
+
+    // `Poison<T>` inherits its auto traits from `T`
+    assert_send::<Poison<i32>>();
+    assert_sync::<Poison<i32>>();
+    assert_unpin::<Poison<i32>>();
+    assert_unwind_safe::<Poison<i32>>();
+    assert_ref_unwind_safe::<Poison<i32>>();
+
+    assert_send::<Poison<Cell<i32>>>();
+    assert_unwind_safe::<Poison<Cell<i32>>>();
+
+    // Interior mutability could break the value through a shared reference without poisoning it
+    assert_not_ref_unwind_safe!(Poison<Cell<i32>>);
+    assert_not_ref_unwind_safe!(Poison<RefCell<i32>>);
+
+    assert_send::<Poison<Vec<String>>>();
+    assert_sync::<Poison<Vec<String>>>();
+    assert_unwind_safe::<Poison<Vec<String>>>();
+    assert_ref_unwind_safe::<Poison<Vec<String>>>();
+
+    assert_send::<Poison<Arc<Mutex<i32>>>>();
+    assert_sync::<Poison<Arc<Mutex<i32>>>>();
+    assert_ref_unwind_safe::<Poison<Arc<Mutex<i32>>>>();
+
+    // Guards are unwind safe regardless of `T`, because they poison the value if a panic
+    // unwinds through them
+    assert_unwind_safe::<PoisonGuard<Cell<i32>>>();
+    assert_unwind_safe::<PoisonGuard<Vec<String>>>();
+
+    assert_send::<PoisonError>();
+    assert_sync::<PoisonError>();
+}
+
+#[test]
+fn poison_value_offset() {
+    // The value field is private, so check its offset through the address `get` returns
+    fn offset_of_value<T>(poison: &Poison<T>) -> usize {
+        let value = poison.get().unwrap() as *const T as usize;
+
+        value - (poison as *const Poison<T> as usize)
+    }
+
+    assert_eq!(0, offset_of_value(&Poison::new(42u64)));
+    assert_eq!(0, offset_of_value(&Poison::new([1u8, 2, 3])));
+
+    let poison = Poison::new(42u64);
+
+    // SAFETY: `Poison<T>` is `#[repr(C)]` with the value as its first field
+    let value = unsafe { *(&poison as *const Poison<u64> as *const u64) };
+
+    assert_eq!(42, value);
+}
+
+#[test]
+fn poison_guard_after_poison_cleared() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let guard = Poison::on_unwind(&mut poison).unwrap_err().recover();
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(1, poison.poison_generation());
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+    *guard += 1;
+    drop(guard);
+
+    assert_eq!(1, *poison.get().unwrap());
+}
 
 #[test]
 fn poison_new_is_unpoisoned() {
@@ -21,6 +205,320 @@ fn poison_new_is_unpoisoned() {
     assert!(poison.get().is_ok());
 }
 
+#[test]
+fn poison_default_is_unpoisoned() {
+    let poison = Poison::<Vec<i32>>::default();
+
+    assert!(!poison.is_poisoned());
+
+    assert!(poison.get().unwrap().is_empty());
+}
+
+#[test]
+fn poison_last_poison_after_recovery() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    let _ = Poison::try_recover(Err::<(), SomeError>(some_err()), guard);
+
+    // The value is still poisoned, so it hasn't been cleared yet
+    assert!(poison.last_poison().is_none());
+
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    let last = poison.last_poison().unwrap();
+
+    assert!(last.downcast_ref::<SomeError>().is_some());
+    assert_eq!(some_err().to_string(), last.message().unwrap().into_owned());
+
+    // Successfully acquiring and releasing guards doesn't clear the last poison
+    *Poison::on_unwind(&mut poison).unwrap() += 1;
+
+    assert!(poison.last_poison().is_some());
+}
+
+#[test]
+fn poison_last_poison_never_poisoned() {
+    let mut poison = Poison::new(0);
+
+    *Poison::on_unwind(&mut poison).unwrap() += 1;
+
+    assert!(poison.last_poison().is_none());
+}
+
+#[test]
+fn poison_with_stats_poisoned_count() {
+    let shared = Arc::new(Mutex::new(Poison::with_stats(0)));
+    let stats = shared.lock().unwrap().stats().unwrap();
+
+    let handles = (0..8)
+        .map(|_| {
+            let shared = shared.clone();
+
+            thread::spawn(move || {
+                let mut guard = match Poison::on_unwind_std(shared.lock()) {
+                    Ok(guard) => guard,
+                    Err(recover) => recover.recover(),
+                };
+
+                *guard += 1;
+
+                panic!("explicit panic");
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert!(handle.join().is_err());
+    }
+
+    assert_eq!(8, stats.poisoned_count());
+
+    let poison = shared.lock().unwrap_or_else(|err| err.into_inner());
+
+    assert_eq!(8, poison.poisoned_count());
+    assert_eq!(8, *poison.peek());
+}
+
+#[test]
+fn poison_without_stats() {
+    let mut poison = Poison::new(0);
+
+    poison.poison_with(some_err()).unwrap();
+
+    assert!(poison.stats().is_none());
+    assert_eq!(0, poison.poisoned_count());
+    assert_eq!(1, poison.poison_generation());
+}
+
+#[test]
+fn poison_new_poisoned_record_round_trip() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let original = PoisonError::from(poison.get().unwrap_err());
+    let record = original.to_record().unwrap();
+
+    // Round-trip the record through its parts, like it would be after being persisted
+    let restored = PoisonRecord::new(
+        record.is_panic(),
+        record.message().map(String::from),
+        record.file(),
+        record.line(),
+        record.column(),
+    );
+
+    let poison = Poison::new_poisoned(PoisonError::from_record(restored), 0);
+
+    assert!(poison.is_poisoned_by_panic());
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(Some(record.clone()), err.to_record());
+    assert_eq!(original.message(), err.message());
+    assert_eq!(original.is_recoverable(), err.is_recoverable());
+    assert!(err.to_string().contains(record.file()));
+}
+
+#[test]
+fn poison_new_poisoned_record_round_trip_code() {
+    let mut poison = Poison::new(0);
+
+    poison.poison_with_code(some_err(), 7).unwrap();
+
+    let record = PoisonError::from(poison.get().unwrap_err())
+        .to_record()
+        .unwrap();
+
+    assert_eq!(Some(7), record.code());
+
+    let mut restored = PoisonRecord::new(
+        record.is_panic(),
+        record.message().map(String::from),
+        record.file(),
+        record.line(),
+        record.column(),
+    );
+
+    if let Some(code) = record.code() {
+        restored = restored.with_code(code);
+    }
+
+    let poison = Poison::new_poisoned(PoisonError::from_record(restored), 0);
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(Some(7), err.code());
+    assert_eq!(Some(record), err.to_record());
+}
+
+#[test]
+fn poison_new_poisoned_unpoisoned_err() {
+    let mut poison = Poison::new(0);
+    poison.freeze().unwrap();
+
+    let frozen = PoisonError::from(Poison::on_unwind(&mut poison).unwrap_err());
+
+    assert_eq!(None, frozen.to_record());
+
+    // Errors that don't represent a poisoned value still poison
+    let poison = Poison::new_poisoned(frozen, 0);
+
+    assert!(poison.is_poisoned());
+    assert!(!poison.is_frozen());
+}
+
+#[test]
+fn poison_map_poison_adds_context() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    let _ = Poison::try_recover(Err::<(), _>(some_err()), guard);
+
+    let original = PoisonError::from(poison.get().unwrap_err())
+        .to_record()
+        .unwrap();
+
+    poison.map_poison(|err| {
+        let record = err.to_record().unwrap();
+
+        PoisonError::from_record(PoisonRecord::new(
+            record.is_panic(),
+            Some(format!("while compacting: {}", record.message().unwrap())),
+            record.file(),
+            record.line(),
+            record.column(),
+        ))
+    });
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+    let mapped = err.to_record().unwrap();
+
+    assert_eq!(
+        Some(format!("while compacting: {}", some_err())).as_deref(),
+        mapped.message()
+    );
+    assert_eq!(
+        (original.file(), original.line(), original.column()),
+        (mapped.file(), mapped.line(), mapped.column())
+    );
+    assert!(err.is_recoverable());
+
+    // Mapping doesn't count as a new poisoning
+    assert_eq!(1, poison.poison_generation());
+}
+
+#[test]
+fn poison_map_poison_context_keeps_source() {
+    let mut poison = Poison::new(0);
+
+    poison.poison_with_code(some_err(), 3).unwrap();
+
+    let original = PoisonError::from(poison.get().unwrap_err());
+
+    poison.map_poison(|err| err.context("while compacting"));
+    poison.map_poison(|err| err.context("while loading"));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(
+        "while loading: while compacting: poisoned by an error",
+        format!("{:#}", err)
+    );
+
+    assert!(err.downcast_ref::<SomeError>().is_some());
+    assert_eq!(some_err().to_string(), err.source().unwrap().to_string());
+    assert_eq!(Some(3), err.code());
+    assert_eq!(original.poisoning_thread(), err.poisoning_thread());
+    assert_eq!(original, err);
+
+    assert_eq!(1, poison.poison_generation());
+}
+
+#[test]
+fn poison_map_poison_context_panic_unchanged() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    poison.map_poison(|err| err.context("while compacting"));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.is_unknown_panic());
+    assert_eq!("poisoned by a panic", format!("{:#}", err));
+}
+
+#[test]
+fn poison_map_poison_unpoisoned() {
+    let mut poison = Poison::new(0);
+
+    poison.map_poison(|_| unreachable!());
+
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+#[cfg(poison_guard_unstable)]
+fn poison_err_provide_location() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+    let acquired_at = PoisonGuard::acquired_at(&guard);
+
+    unwind_through_guard(guard);
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(
+        Some(acquired_at),
+        std::error::request_ref::<panic::Location>(&err)
+    );
+}
+
+#[test]
+fn poison_err_display_alternate() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+    let acquired_at = PoisonGuard::acquired_at(&guard).to_string();
+
+    unwind_through_guard(guard);
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(format!("{}", err).contains(&acquired_at));
+
+    assert_eq!("poisoned by a panic", format!("{:#}", err));
+
+    let mut poison = Poison::new(0);
+    poison.poison_with(some_err()).unwrap();
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(format!("{}", err).starts_with("poisoned by an error ("));
+    assert_eq!("poisoned by an error", format!("{:#}", err));
+}
+
+#[test]
+fn poison_from_value_is_unpoisoned() {
+    let poison: Poison<i32> = 42.into();
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(42, *poison.get().unwrap());
+
+    let poisons = Vec::<Poison<i32>>::from_iter([1, 2, 3].into_iter().map(Poison::from));
+
+    assert_eq!(
+        vec![1, 2, 3],
+        poisons
+            .iter()
+            .map(|poison| *poison.get().unwrap())
+            .collect::<Vec<_>>()
+    );
+}
+
 #[test]
 fn poison_new_catch_unwind() {
     let poison = Poison::new_catch_unwind(|| 0);
@@ -38,50 +536,1073 @@ fn poison_new_catch_unwind_panic() {
 }
 
 #[test]
-fn poison_try_new_catch_unwind() {
-    let poison = Poison::try_new_catch_unwind(|| Ok::<i32, SomeError>(0));
+fn poison_new_catch_unwind_or() {
+    // This type doesn't implement `Default`
+    #[derive(Debug, PartialEq)]
+    struct Handle(i32);
+
+    let poison = Poison::new_catch_unwind_or(Handle(-1), || Handle(3));
 
     assert!(!poison.is_poisoned());
-    assert!(poison.get().is_ok());
+    assert_eq!(&Handle(3), poison.get().unwrap());
+
+    let poison = Poison::new_catch_unwind_or(Handle(-1), || panic!("explicit panic"));
+
+    assert!(poison.is_poisoned_by_panic());
+    assert_eq!(&Handle(-1), poison.peek());
 }
 
 #[test]
-fn poison_try_new_catch_unwind_err() {
-    let poison = Poison::try_new_catch_unwind(|| Err::<i32, SomeError>(some_err()));
+fn poison_new_catch_unwind_panic_captured() {
+    let poison: Poison<i32> = Poison::new_catch_unwind(|| panic!("explicit panic"));
 
-    assert!(poison.is_poisoned());
-    assert!(poison.get().is_err());
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.is_captured_panic());
+    assert!(!err.is_unknown_panic());
 }
 
 #[test]
-fn poison_try_new_catch_unwind_panic() {
-    fn try_new_catch_unwind() -> Result<i32, SomeError> {
-        panic!("explicit panic")
+fn poison_new_catch_unwind_panic_custom_payload() {
+    struct CustomPayload;
+
+    let poison: Poison<i32> = Poison::new_catch_unwind(|| panic::panic_any(CustomPayload));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.is_unknown_panic());
+    assert!(!err.is_captured_panic());
+}
+
+#[test]
+fn poison_new_catch_unwind_with_hook_custom_payload() {
+    struct CustomPayload;
+
+    let poison: Poison<i32> =
+        Poison::new_catch_unwind_with_hook(|| panic::panic_any(CustomPayload));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.is_captured_panic());
+    assert!(err.to_string().contains("panicked at"));
+    assert!(err.to_string().contains(file!()));
+}
+
+#[test]
+fn poison_new_catch_unwind_with_hook_string_payload() {
+    let poison: Poison<i32> = Poison::new_catch_unwind_with_hook(|| panic!("explicit panic"));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.is_captured_panic());
+    assert!(err.to_string().contains("'explicit panic'"));
+}
+
+#[test]
+fn poison_new_catch_unwind_with_hook_concurrent() {
+    struct CustomPayload;
+
+    let handles = (0..8)
+        .map(|_| {
+            thread::spawn(|| {
+                let poison: Poison<i32> =
+                    Poison::new_catch_unwind_with_hook(|| panic::panic_any(CustomPayload));
+
+                PoisonError::from(poison.get().unwrap_err()).to_string()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Every thread captures its own panic message
+    for handle in handles {
+        assert!(handle.join().unwrap().contains("panicked at"));
     }
+}
 
-    let poison: Poison<i32> = Poison::try_new_catch_unwind(try_new_catch_unwind);
+#[test]
+fn poison_new_catch_unwind_with_hook_unpoisoned() {
+    let poison = Poison::new_catch_unwind_with_hook(|| 42);
 
-    assert!(poison.is_poisoned());
-    assert!(poison.get().is_err());
+    assert_eq!(42, *poison.get().unwrap());
 }
 
 #[test]
-fn poison_get_unpoisoned() {
-    let poison = Poison::new(0);
+fn poison_unwind_through_guard_is_unknown_panic() {
+    let mut poison = Poison::new(0);
 
-    assert_eq!(0, *poison.get().unwrap());
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.is_unknown_panic());
 }
 
 #[test]
-fn poison_get_poisoned() {
+fn poison_err_downcast_ref() {
+    #[derive(Debug)]
+    struct CustomError(i32);
+
+    impl fmt::Display for CustomError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "custom error {}", self.0)
+        }
+    }
+
+    impl Error for CustomError {}
+
     let mut poison = Poison::new(0);
 
-    // Drop a guard without recovering, this will leave the value poisoned
-    drop(Poison::unless_recovered(&mut poison).unwrap());
+    poison.poison_with(CustomError(42)).unwrap();
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(42, err.downcast_ref::<CustomError>().unwrap().0);
+    assert!(err.downcast_ref::<SomeError>().is_none());
+
+    // Errors from failed recoveries can also be downcast
+    let _ = Poison::on_unwind(&mut poison)
+        .unwrap_err()
+        .try_recover_with(|_| Err(CustomError(43)));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(43, err.downcast_ref::<CustomError>().unwrap().0);
+}
+
+#[test]
+fn poison_err_poisoning_thread() {
+    let mut poison = Poison::new(0);
+
+    let id = thread::scope(|scope| {
+        thread::Builder::new()
+            .name("poisoner".into())
+            .spawn_scoped(scope, || {
+                err_through_guard(Poison::unless_recovered(&mut poison).unwrap());
+
+                thread::current().id()
+            })
+            .unwrap()
+            .join()
+            .unwrap()
+    });
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(Some((id, Some("poisoner".into()))), err.poisoning_thread());
+    assert!(err.to_string().contains("'poisoner'"));
+}
+
+#[test]
+fn poison_is_poisoned_by() {
+    let unpoisoned = Poison::new(0);
+    assert!(!unpoisoned.is_poisoned_by_panic());
+    assert!(!unpoisoned.is_poisoned_by_error());
+
+    let mut panicked = Poison::new(0);
+    unwind_through_guard(Poison::on_unwind(&mut panicked).unwrap());
+    assert!(panicked.is_poisoned_by_panic());
+    assert!(!panicked.is_poisoned_by_error());
+
+    let mut errored = Poison::new(0);
+    errored.poison_with(some_err()).unwrap();
+    assert!(!errored.is_poisoned_by_panic());
+    assert!(errored.is_poisoned_by_error());
+
+    let mut dropped = Poison::new(0);
+    err_through_guard(Poison::unless_recovered(&mut dropped).unwrap());
+    assert!(!dropped.is_poisoned_by_panic());
+    assert!(dropped.is_poisoned_by_error());
+}
+
+#[test]
+fn poison_err_message() {
+    let panicked: Poison<i32> = Poison::new_catch_unwind(|| panic!("explicit panic"));
+    let err = PoisonError::from(panicked.get().unwrap_err());
+    assert_eq!(Some("explicit panic"), err.message().as_deref());
+
+    let mut errored = Poison::new(0);
+    errored
+        .poison_with(io::Error::other("explicit error"))
+        .unwrap();
+    let err = PoisonError::from(errored.get().unwrap_err());
+    assert_eq!(Some("explicit error"), err.message().as_deref());
+
+    let mut unknown = Poison::new(0);
+    unwind_through_guard(Poison::on_unwind(&mut unknown).unwrap());
+    let err = PoisonError::from(unknown.get().unwrap_err());
+    assert_eq!(None, err.message());
+}
+
+#[test]
+fn poison_err_code() {
+    let mut poison = Poison::new(0);
+
+    poison.poison_with_code(some_err(), 42).unwrap();
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(Some(42), err.code());
+    assert!(err.downcast_ref::<SomeError>().is_some());
+
+    // Errors without a code don't have one
+    let mut poison = Poison::new(0);
+
+    poison.poison_with(some_err()).unwrap();
+
+    assert_eq!(None, PoisonError::from(poison.get().unwrap_err()).code());
+}
+
+#[test]
+fn poison_err_eq() {
+    fn poisoned(panic: bool) -> PoisonError {
+        let mut poison = Poison::new(0);
+
+        let guard = Poison::on_unwind(&mut poison).unwrap();
+
+        if panic {
+            unwind_through_guard(guard);
+        } else {
+            let _ = PoisonGuard::downgrade_to_recover(guard);
+        }
+
+        PoisonError::from(poison.get().unwrap_err())
+    }
+
+    assert_eq!(poisoned(true), poisoned(true));
+    assert_eq!(poisoned(false), poisoned(false));
+
+    // Errors poisoned in different ways aren't equal
+    assert_ne!(poisoned(true), poisoned(false));
+
+    // Errors poisoned at different locations aren't equal
+    let mut poison = Poison::new(0);
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    assert_ne!(poisoned(true), PoisonError::from(poison.get().unwrap_err()));
+}
+
+#[test]
+fn poison_err_eq_restored() {
+    fn restored(panic: bool, message: &str, code: u32, line: u32) -> PoisonError {
+        PoisonError::from_record(
+            PoisonRecord::new(panic, Some(message.into()), "src/lib.rs", line, 1).with_code(code),
+        )
+    }
+
+    // Messages and codes aren't compared
+    assert_eq!(restored(true, "a", 1, 42), restored(true, "b", 2, 42));
+
+    // Errors poisoned in different ways or at different locations aren't equal
+    assert_ne!(restored(true, "a", 1, 42), restored(false, "a", 1, 42));
+    assert_ne!(restored(true, "a", 1, 42), restored(true, "a", 1, 43));
+}
+
+#[test]
+fn poison_err_from_std_poison() {
+    let mutex = std::sync::Mutex::new(0);
+
+    thread::scope(|scope| {
+        let _ = scope
+            .spawn(|| {
+                let _guard = mutex.lock().unwrap();
+
+                panic!("explicit panic");
+            })
+            .join();
+    });
+
+    let err = PoisonError::from(mutex.lock().unwrap_err());
+
+    assert!(err.is_unknown_panic());
+    assert!(!err.is_recoverable());
+    assert!(err.poisoning_thread().is_none());
+    assert!(err.to_string().contains(file!()));
+
+    // The location is where the conversion happened, not where a guard was acquired
+    assert!(err.to_string().contains("converted from a poisoned lock"));
+    assert!(!err.to_string().contains("guard was acquired"));
+
+    // The std guard is dropped by the conversion, so the lock isn't held
+    assert!(matches!(
+        mutex.try_lock(),
+        Err(std::sync::TryLockError::Poisoned(_))
+    ));
+}
+
+#[test]
+fn poison_err_is_recoverable() {
+    fn err(poison: &Poison<i32>) -> PoisonError {
+        PoisonError::from(poison.get().unwrap_err())
+    }
+
+    let captured_panic: Poison<i32> = Poison::new_catch_unwind(|| panic!("explicit panic"));
+    assert!(!err(&captured_panic).is_recoverable());
+
+    let mut unknown_panic = Poison::new(0);
+    unwind_through_guard(Poison::on_unwind(&mut unknown_panic).unwrap());
+    assert!(!err(&unknown_panic).is_recoverable());
+
+    let mut captured_err = Poison::new(0);
+    captured_err.poison_with(some_err()).unwrap();
+    assert!(err(&captured_err).is_recoverable());
+
+    let mut unknown_err = Poison::new(0);
+    err_through_guard(Poison::unless_recovered(&mut unknown_err).unwrap());
+    assert!(err(&unknown_err).is_recoverable());
+
+    let mut guarded = Poison::new(0);
+    mem::forget(Poison::on_unwind(&mut guarded).unwrap());
+    assert!(err(&guarded).is_recoverable());
+}
+
+#[test]
+fn poison_try_new_catch_unwind() {
+    let poison = Poison::try_new_catch_unwind(|| Ok::<i32, SomeError>(0));
+
+    assert!(!poison.is_poisoned());
+    assert!(poison.get().is_ok());
+}
+
+#[test]
+fn poison_try_new_catch_unwind_err() {
+    let poison = Poison::try_new_catch_unwind(|| Err::<i32, SomeError>(some_err()));
+
+    assert!(poison.is_poisoned());
+    assert!(poison.get().is_err());
+}
+
+#[test]
+fn poison_try_new_catch_unwind_panic() {
+    fn try_new_catch_unwind() -> Result<i32, SomeError> {
+        panic!("explicit panic")
+    }
+
+    let poison: Poison<i32> = Poison::try_new_catch_unwind(try_new_catch_unwind);
+
+    assert!(poison.is_poisoned());
+    assert!(poison.get().is_err());
+}
+
+#[test]
+fn poison_get_unpoisoned() {
+    let poison = Poison::new(0);
+
+    assert_eq!(0, *poison.get().unwrap());
+}
+
+#[test]
+fn poison_get_poisoned() {
+    let mut poison = Poison::new(0);
+
+    // Drop a guard without recovering, this will leave the value poisoned
+    drop(Poison::unless_recovered(&mut poison).unwrap());
 
     assert!(poison.get().is_err());
 }
 
+#[test]
+fn poison_get_or_recover_with_unpoisoned() {
+    let mut poison = Poison::new(0);
+
+    let v = poison.get_or_recover_with(|_| panic!("recovery shouldn't run"));
+
+    assert_eq!(0, *v);
+}
+
+#[test]
+fn poison_get_or_recover_with_poisoned() {
+    let mut poison = Poison::new(0);
+
+    drop(Poison::unless_recovered(&mut poison).unwrap());
+
+    let v = poison.get_or_recover_with(|v| *v += 1);
+
+    assert_eq!(1, *v);
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+fn poison_try_deref_unpoisoned() {
+    let poison = Poison::new(0);
+
+    assert_eq!(Some(&0), poison.try_deref());
+}
+
+#[test]
+fn poison_try_deref_poisoned() {
+    let mut poison = Poison::new(0);
+
+    drop(Poison::unless_recovered(&mut poison).unwrap());
+
+    assert!(poison.try_deref().is_none());
+}
+
+#[test]
+fn poison_into_poisoned_parts_poisoned() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    let mut guard = Poison::unless_recovered(&mut poison).unwrap();
+    let _ = PoisonGuard::try_with(&mut guard, |v| {
+        v.push(4);
+
+        Err::<(), SomeError>(some_err())
+    });
+    drop(guard);
+
+    let (value, err) = poison.into_poisoned_parts().ok().unwrap();
+
+    assert_eq!(vec![1, 2, 3, 4], value);
+    assert!(err.downcast_ref::<SomeError>().is_some());
+}
+
+#[test]
+fn poison_into_poisoned_parts_unpoisoned() {
+    let poison = Poison::new(vec![1, 2, 3]);
+
+    let poison = poison.into_poisoned_parts().err().unwrap();
+
+    assert_eq!(&[1, 2, 3], &**poison.get().unwrap());
+}
+
+#[test]
+fn poison_try_clone_unpoisoned() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    let mut clone = poison.try_clone().unwrap();
+
+    // The clone is independent of the original
+    Poison::on_unwind(&mut clone).unwrap().push(4);
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(!clone.is_poisoned());
+    assert_eq!(&[1, 2, 3, 4], &**clone.get().unwrap());
+    assert_eq!(0, clone.poison_generation());
+}
+
+#[test]
+fn poison_try_clone_poisoned() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let err = poison.try_clone().err().unwrap();
+
+    assert!(err.is_unknown_panic());
+}
+
+#[test]
+fn poison_iter_unpoisoned() {
+    let poison = Poison::new(vec![1, 2, 3]);
+
+    assert_eq!(
+        vec![2, 4, 6],
+        poison.iter().unwrap().map(|i| i * 2).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn poison_iter_poisoned() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    drop(Poison::unless_recovered(&mut poison).unwrap());
+
+    assert!(poison.iter().is_err());
+}
+
+#[test]
+fn poison_freeze() {
+    let mut poison = Poison::new(42);
+
+    poison.freeze().unwrap();
+
+    assert!(poison.is_frozen());
+    assert!(!poison.is_poisoned());
+
+    // Reads still work
+    assert_eq!(42, *poison.get().unwrap());
+    assert_eq!(42, *poison.peek());
+
+    // Guards can't be acquired
+    let recover = Poison::on_unwind(&mut poison).unwrap_err();
+    assert!(recover.is_frozen());
+
+    let err = recover.into_error();
+    assert!(err.is_frozen());
+    assert!(!err.is_recoverable());
+
+    let err = PoisonError::from(Poison::unless_recovered(&mut poison).unwrap_err());
+    assert!(err.is_frozen());
+
+    // Frozen values can't be poisoned
+    let err = poison.poison_with(some_err()).unwrap_err();
+    assert!(err.is_frozen());
+
+    let err = poison.poison_with_code(some_err(), 1).unwrap_err();
+    assert!(err.is_frozen());
+
+    assert!(!poison.is_poisoned());
+    assert!(poison.is_frozen());
+}
+
+#[test]
+fn poison_freeze_poisoned() {
+    let mut poison = Poison::new(42);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let err = poison.freeze().unwrap_err();
+
+    assert!(err.is_unknown_panic());
+    assert!(!poison.is_frozen());
+}
+
+#[test]
+fn poison_freeze_try_recover() {
+    let mut poison = Poison::new(42);
+
+    poison.freeze().unwrap();
+
+    // Fallible recovery returns an error without calling the closure
+    let recover = Poison::on_unwind(&mut poison)
+        .unwrap_err()
+        .try_recover_with(|_| -> Result<(), SomeError> { unreachable!() })
+        .unwrap_err();
+
+    assert!(recover.is_frozen());
+
+    let err = recover
+        .recover_or_propagate(|_| true, |_| unreachable!())
+        .unwrap_err();
+
+    assert!(err.is_frozen());
+    assert!(poison.is_frozen());
+    assert_eq!(42, *poison.get().unwrap());
+}
+
+#[test]
+#[should_panic(expected = "frozen")]
+fn poison_freeze_recover() {
+    let mut poison = Poison::new(42);
+
+    poison.freeze().unwrap();
+
+    let _ = Poison::on_unwind(&mut poison)
+        .unwrap_err()
+        .recover_with(|v| *v += 1);
+}
+
+#[test]
+fn poison_as_deref() {
+    let some = Poison::new(Some(String::from("a value")));
+    assert_eq!(Some("a value"), some.as_deref().unwrap());
+
+    let none = Poison::new(None::<String>);
+    assert_eq!(None, none.as_deref().unwrap());
+
+    let mut poisoned = Poison::new(Some(String::from("a value")));
+    unwind_through_guard(Poison::on_unwind(&mut poisoned).unwrap());
+    assert!(poisoned.as_deref().unwrap_err().is_unknown_panic());
+}
+
+#[test]
+fn poison_get_or_init_first_call() {
+    let mut poison = Poison::new(None);
+
+    assert_eq!(&42, poison.get_or_init(|| 42).unwrap());
+    assert_eq!(Some(&42), poison.get().unwrap().as_ref());
+}
+
+#[test]
+fn poison_get_or_init_cached() {
+    let calls = Cell::new(0);
+    let mut poison = Poison::new(None);
+
+    for _ in 0..3 {
+        let value = poison
+            .get_or_init(|| {
+                calls.set(calls.get() + 1);
+
+                String::from("a value")
+            })
+            .unwrap();
+
+        assert_eq!("a value", value);
+    }
+
+    assert_eq!(1, calls.get());
+}
+
+#[test]
+fn poison_get_or_init_panic_poisons() {
+    let mut poison = Poison::<Option<i32>>::new(None);
+
+    let err = poison.get_or_init(|| panic!("explicit panic")).unwrap_err();
+
+    assert!(err.is_captured_panic());
+    assert!(poison.is_poisoned_by_panic());
+
+    // Later calls don't try initialize the poisoned value again
+    assert!(poison.get_or_init(|| unreachable!()).is_err());
+}
+
+#[test]
+fn poison_get_cloned_unpoisoned() {
+    let poison = Poison::new(vec![1, 2, 3]);
+
+    assert_eq!(vec![1, 2, 3], poison.get_cloned().unwrap());
+}
+
+#[test]
+fn poison_get_cloned_poisoned() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    poison.poison_with(some_err()).unwrap();
+
+    assert!(poison.get_cloned().unwrap_err().is_recoverable());
+}
+
+#[test]
+fn poison_peek_poisoned() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+    *guard = 42;
+    unwind_through_guard(guard);
+
+    assert!(poison.is_poisoned());
+    assert_eq!(42, *poison.peek());
+
+    // Peeking doesn't recover the value
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn poison_recover_if_matches() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    poison.poison_with_code(some_err(), 7).unwrap();
+
+    poison
+        .recover_if(|err| err.code() == Some(7), |v| v.clear())
+        .unwrap();
+
+    assert!(!poison.is_poisoned());
+    assert!(poison.get().unwrap().is_empty());
+}
+
+#[test]
+fn poison_recover_if_no_match() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    poison.poison_with_code(some_err(), 7).unwrap();
+
+    let err = poison
+        .recover_if(|err| err.code() == Some(8), |_| unreachable!())
+        .unwrap_err();
+
+    assert_eq!(Some(7), err.code());
+
+    assert!(poison.is_poisoned());
+    assert_eq!(&[1, 2, 3], &**poison.peek());
+}
+
+#[test]
+fn poison_recover_if_unpoisoned() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    poison
+        .recover_if(|_| unreachable!(), |_| unreachable!())
+        .unwrap();
+
+    assert_eq!(&[1, 2, 3], &**poison.get().unwrap());
+}
+
+#[test]
+fn poison_take_unpoisoned() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    assert_eq!(vec![1, 2, 3], poison.take().unwrap());
+
+    assert!(!poison.is_poisoned());
+    assert!(poison.get().unwrap().is_empty());
+}
+
+#[test]
+fn poison_take_poisoned() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(poison.take().unwrap_err().is_unknown_panic());
+
+    assert!(poison.is_poisoned());
+    assert_eq!(&[1, 2, 3], &**poison.peek());
+}
+
+#[test]
+fn poison_update() {
+    let mut poison = Poison::new(String::from("a"));
+
+    poison
+        .update(|mut s| {
+            s.push('b');
+            s
+        })
+        .unwrap();
+
+    assert_eq!("ab", *poison.get().unwrap());
+}
+
+#[test]
+fn poison_update_panic() {
+    let mut poison = Poison::new(String::from("a"));
+
+    let err = poison.update(|_| panic!("explicit panic")).unwrap_err();
+
+    assert!(err.is_captured_panic());
+    assert!(poison.is_poisoned_by_panic());
+
+    // The value was lost, so it's left as its default
+    assert_eq!("", *poison.peek());
+
+    // Poisoned values aren't updated
+    assert!(poison.update(|_| unreachable!()).is_err());
+}
+
+#[test]
+fn poison_catch_account() {
+    struct Account(Poison<AccountState>);
+
+    struct AccountState {
+        total: i64,
+        // Invariant: the total must be the sum of the changes
+        changes: Vec<i64>,
+    }
+
+    impl Account {
+        fn push_change(&mut self, change: i64) -> Result<(), PoisonError> {
+            crate::catch(&mut self.0, |state| {
+                state.changes.push(change);
+
+                if change == i64::MAX {
+                    panic!("explicit panic");
+                }
+
+                state.total += change;
+            })
+        }
+
+        fn total(&mut self) -> Result<i64, PoisonError> {
+            crate::catch(&mut self.0, |state| state.total)
+        }
+    }
+
+    let mut account = Account(Poison::new(AccountState {
+        total: 0,
+        changes: vec![],
+    }));
+
+    account.push_change(1).unwrap();
+    account.push_change(2).unwrap();
+
+    assert_eq!(3, account.total().unwrap());
+
+    // Panicking while pushing a change breaks the invariant, so the account is poisoned
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| account.push_change(i64::MAX)));
+
+    let err = account.push_change(3).unwrap_err();
+    assert!(err.is_unknown_panic());
+
+    assert!(account.total().is_err());
+    assert_eq!(3, account.0.peek().changes.len());
+}
+
+#[test]
+fn guard_scope_ok() {
+    let mut value = vec![1, 2, 3];
+
+    let len = crate::guard_scope(
+        &mut value,
+        |value| {
+            value.push(4);
+            value.len()
+        },
+        |_| unreachable!(),
+    );
+
+    assert_eq!(4, len);
+    assert_eq!(vec![1, 2, 3, 4], value);
+}
+
+#[test]
+fn guard_scope_cleanup_on_panic() {
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Resource;
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let cleanups = Cell::new(0);
+    let mut resources = vec![Resource, Resource];
+
+    let panic = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        crate::guard_scope(
+            &mut resources,
+            |resources| {
+                resources.push(Resource);
+
+                panic!("explicit panic");
+            },
+            |resources| {
+                cleanups.set(cleanups.get() + 1);
+
+                // All resources are released before the panic continues
+                resources.clear();
+                assert_eq!(3, DROPPED.load(Ordering::SeqCst));
+            },
+        )
+    }))
+    .unwrap_err();
+
+    assert_eq!(Some(&"explicit panic"), panic.downcast_ref::<&str>());
+
+    assert_eq!(1, cleanups.get());
+    assert!(resources.is_empty());
+    assert_eq!(3, DROPPED.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn poison_async_propagate() {
+    async fn push(poison: &mut Poison<Vec<i32>>, value: i32) -> Result<usize, PoisonError> {
+        let mut guard = Poison::on_unwind(poison)?;
+
+        tokio::task::yield_now().await;
+
+        guard.push(value);
+
+        Ok(guard.len())
+    }
+
+    async fn push_failed(poison: &mut Poison<Vec<i32>>) -> Result<(), PoisonError> {
+        let guard = Poison::unless_recovered(poison)?;
+
+        tokio::task::yield_now().await;
+
+        // Return early without recovering the guard, poisoning the value
+        drop(guard);
+
+        Ok(())
+    }
+
+    let mut poison = Poison::new(Vec::new());
+
+    assert_eq!(1, push(&mut poison, 1).await.unwrap());
+
+    push_failed(&mut poison).await.unwrap();
+
+    let err = push(&mut poison, 2).await.unwrap_err();
+
+    assert!(err.was_guard_drop());
+    assert_eq!(&[1], &**poison.peek());
+}
+
+#[tokio::test]
+async fn poison_async_cancel_unless_recovered() {
+    let mut poison = Poison::new(0);
+
+    {
+        let fut = async {
+            let mut guard = Poison::unless_recovered(&mut poison).unwrap();
+
+            *guard += 1;
+
+            std::future::pending::<()>().await;
+
+            Poison::recover(guard);
+        };
+
+        // Poll the future once so it acquires the guard, then cancel it
+        let _ = tokio::time::timeout(std::time::Duration::from_millis(1), fut).await;
+    }
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn poison_swap() {
+    let mut poisoned = Poison::new(1);
+    let mut unpoisoned = Poison::new(2);
+
+    drop(Poison::unless_recovered(&mut poisoned).unwrap());
+
+    poisoned.swap(&mut unpoisoned);
+
+    // The poison state follows the value
+    assert_eq!(2, *poisoned.get().unwrap());
+    assert!(unpoisoned.is_poisoned());
+
+    let guard = Poison::on_unwind(&mut unpoisoned).unwrap_err().recover();
+    assert_eq!(1, *guard);
+}
+
+#[test]
+fn poison_poison_with() {
+    let mut poison = Poison::new(0);
+
+    poison.poison_with(some_err()).unwrap();
+
+    assert!(poison.is_poisoned());
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert_eq!(
+        io::ErrorKind::Other,
+        err.source()
+            .unwrap()
+            .downcast_ref::<SomeError>()
+            .unwrap()
+            .kind()
+    );
+}
+
+#[test]
+fn poison_generation() {
+    let mut poison = Poison::new(0);
+
+    assert_eq!(0, poison.poison_generation());
+
+    // Guards that only poison on unwind don't advance the generation when they're released
+    drop(Poison::on_unwind(&mut poison).unwrap());
+
+    assert_eq!(0, poison.poison_generation());
+
+    // Guards that must be recovered don't advance the generation when they're recovered
+    Poison::recover(Poison::unless_recovered(&mut poison).unwrap());
+
+    assert_eq!(0, poison.poison_generation());
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    err_through_guard(Poison::unless_recovered(&mut poison).unwrap());
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    drop(Poison::unless_recovered(&mut poison).unwrap());
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(3, poison.poison_generation());
+}
+
+#[test]
+fn poison_generation_forgotten_unless_recovered() {
+    let mut poison = Poison::new(0);
+
+    mem::forget(Poison::unless_recovered(&mut poison).unwrap());
+
+    assert!(poison.is_poisoned());
+    assert_eq!(1, poison.poison_generation());
+
+    // Recovering the value remembers the forgotten guard
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    assert_eq!(1, poison.poison_generation());
+    assert!(poison.last_poison().is_some());
+}
+
+#[test]
+fn poison_unless_recovered_recover_not_observed() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut poison = Poison::with_stats(0);
+
+    poison.on_poison({
+        let calls = calls.clone();
+        move |_| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    Poison::recover(Poison::unless_recovered(&mut poison).unwrap());
+
+    assert_eq!(0, calls.load(Ordering::Relaxed));
+    assert_eq!(0, poison.poisoned_count());
+    assert!(poison.last_poison().is_none());
+
+    err_through_guard(Poison::unless_recovered(&mut poison).unwrap());
+
+    assert_eq!(1, calls.load(Ordering::Relaxed));
+    assert_eq!(1, poison.poisoned_count());
+}
+
+#[test]
+fn poison_generation_new_catch_unwind_panic() {
+    let poison: Poison<i32> = Poison::new_catch_unwind(|| panic!("explicit panic"));
+
+    assert_eq!(1, poison.poison_generation());
+}
+
+#[test]
+fn poison_hook() {
+    thread_local! {
+        static CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    fn hook(_: &PoisonError) {
+        CALLS.with(|calls| calls.set(calls.get() + 1));
+    }
+
+    crate::set_poison_hook(hook);
+
+    let mut poison = Poison::new(0);
+
+    // Acquiring and releasing guards without poisoning doesn't call the hook
+    drop(Poison::on_unwind(&mut poison).unwrap());
+    assert_eq!(0, CALLS.with(Cell::get));
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    err_through_guard(Poison::unless_recovered(&mut poison).unwrap());
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    poison.poison_with(some_err()).unwrap();
+
+    crate::take_poison_hook();
+
+    assert_eq!(3, CALLS.with(Cell::get));
+}
+
+#[test]
+fn poison_on_poison() {
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let mut poison = Poison::new(0);
+
+    poison.on_poison({
+        let calls = calls.clone();
+
+        move |_| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    drop(Poison::on_unwind(&mut poison).unwrap());
+    assert_eq!(0, calls.load(Ordering::Relaxed));
+
+    err_through_guard(Poison::unless_recovered(&mut poison).unwrap());
+    assert_eq!(1, calls.load(Ordering::Relaxed));
+
+    // Replacing the reason an already poisoned value was poisoned doesn't call the callback
+    let _ = Poison::on_unwind(&mut poison)
+        .unwrap_err()
+        .try_recover_with(|_| Err(some_err()));
+    assert_eq!(1, calls.load(Ordering::Relaxed));
+}
+
 #[test]
 fn poison_recover_into_error() {
     fn try_with(v: &mut Poison<i32>) -> Result<(), Box<dyn Error + 'static>> {
@@ -0,0 +1,68 @@
+use crate::{
+    poison::PoisonError,
+    tests::{
+        some_err,
+        SomeError,
+    },
+    Poison,
+};
+use anyhow::Context;
+use std::{
+    error::Error,
+    iter,
+};
+
+#[test]
+fn anyhow_err_round_trip() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+
+    let err = Err::<(), SomeError>(some_err())
+        .context("while reading segment 5")
+        .context("while compacting")
+        .unwrap_err();
+
+    Poison::err_anyhow(guard, err);
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+    let anyhow = err.as_anyhow().unwrap();
+
+    // The original error keeps its context chain
+    assert_eq!(
+        vec![
+            "while compacting".to_owned(),
+            "while reading segment 5".to_owned(),
+            some_err().to_string(),
+        ],
+        anyhow
+            .chain()
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>()
+    );
+    assert!(anyhow.root_cause().downcast_ref::<SomeError>().is_some());
+
+    // The chain is also visible through the poison error
+    let chain = iter::successors(err.source(), |&err| err.source())
+        .map(|err| err.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        anyhow
+            .chain()
+            .map(|err| err.to_string())
+            .collect::<Vec<_>>(),
+        chain
+    );
+}
+
+#[test]
+fn anyhow_err_not_anyhow() {
+    let mut poison = Poison::new(0);
+
+    poison.poison_with(some_err()).unwrap();
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.as_anyhow().is_none());
+}
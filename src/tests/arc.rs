@@ -0,0 +1,77 @@
+use crate::{
+    OwnedPoisonGuard,
+    Poison,
+};
+use parking_lot::Mutex;
+use std::{
+    sync::Arc,
+    thread,
+};
+
+fn lock(shared: &Arc<Mutex<Poison<Vec<i32>>>>) -> OwnedPoisonGuard<Vec<i32>> {
+    Poison::on_unwind_arc(shared).unwrap()
+}
+
+#[test]
+fn arc_on_unwind_outlives_scope() {
+    let shared = Arc::new(Mutex::new(Poison::new(Vec::new())));
+
+    let handles = (0..4)
+        .map(|i| {
+            let shared = shared.clone();
+
+            thread::spawn(move || {
+                // The guard outlives the borrow of the `Arc` it was acquired through
+                let mut guard = lock(&shared);
+                drop(shared);
+
+                guard.push(i);
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let mut values = shared.lock().get().unwrap().clone();
+    values.sort();
+
+    assert_eq!(vec![0, 1, 2, 3], values);
+}
+
+#[test]
+fn arc_on_unwind_poisons_on_panic() {
+    let shared = Arc::new(Mutex::new(Poison::new(0)));
+
+    let _ = thread::spawn({
+        let shared = shared.clone();
+
+        move || {
+            let _guard = Poison::on_unwind_arc(&shared).unwrap();
+
+            panic!("explicit panic");
+        }
+    })
+    .join();
+
+    assert!(shared.lock().is_poisoned_by_panic());
+
+    // The lock was released while unwinding
+    assert!(Poison::on_unwind_arc(&shared).is_err());
+}
+
+#[test]
+fn arc_unless_recovered() {
+    let shared = Arc::new(Mutex::new(Poison::new(0)));
+
+    let guard = Poison::unless_recovered_arc(&shared).unwrap();
+    drop(guard);
+
+    assert!(shared.lock().is_poisoned());
+
+    let guard = Poison::unless_recovered_arc(&shared).unwrap_err().recover();
+    Poison::recover(guard);
+
+    assert!(!shared.lock().is_poisoned());
+}
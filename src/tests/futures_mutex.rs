@@ -0,0 +1,70 @@
+use crate::{
+    tests::{
+        some_err,
+        SomeError,
+    },
+    Poison,
+};
+use futures::lock::Mutex;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn futures_mutex_on_unwind() {
+    let mutex = Mutex::new(Poison::new(0));
+
+    {
+        let mut guard = Poison::on_unwind(mutex.lock().await).unwrap();
+
+        tokio::task::yield_now().await;
+
+        *guard += 1;
+    }
+
+    assert_eq!(1, *mutex.lock().await.get().unwrap());
+}
+
+#[tokio::test]
+async fn futures_mutex_on_unwind_poisons_on_panic() {
+    let mutex = Arc::new(Mutex::new(Poison::new(0)));
+
+    let handle = {
+        let mutex = mutex.clone();
+
+        // The guard is held across an await, so it needs to be `Send` to be spawned
+        tokio::spawn(async move {
+            let mut guard = Poison::on_unwind(mutex.lock().await).unwrap();
+
+            *guard += 1;
+
+            tokio::task::yield_now().await;
+
+            panic!("explicit panic");
+        })
+    };
+
+    assert!(handle.await.unwrap_err().is_panic());
+
+    // `futures::lock::Mutex` doesn't poison, so the next lock observes the `Poison<T>`'s state
+    assert!(mutex.lock().await.is_poisoned_by_panic());
+
+    let recover = Poison::on_unwind(mutex.lock().await).err().unwrap();
+
+    assert_eq!(1, *recover.recover());
+
+    assert!(!mutex.lock().await.is_poisoned());
+}
+
+#[tokio::test]
+async fn futures_mutex_unless_recovered_poisons_on_err() {
+    let mutex = Mutex::new(Poison::new(0));
+
+    {
+        let guard = Poison::unless_recovered(mutex.lock().await).unwrap();
+
+        tokio::task::yield_now().await;
+
+        let _ = Poison::try_recover(Err::<(), SomeError>(some_err()), guard);
+    }
+
+    assert!(mutex.lock().await.is_poisoned());
+}
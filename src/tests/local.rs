@@ -0,0 +1,106 @@
+use crate::{
+    local::LocalPoison,
+    Poison,
+};
+use std::{
+    error::Error,
+    fmt,
+    rc::Rc,
+};
+
+#[derive(Debug)]
+struct RcError(Rc<str>);
+
+impl fmt::Display for RcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Error for RcError {}
+
+#[test]
+fn local_poison_with() {
+    let mut poison = LocalPoison::new(0);
+
+    poison
+        .poison_with(RcError(Rc::from("explicit error")))
+        .unwrap();
+
+    assert!(poison.is_poisoned());
+
+    let err = poison.local_error().unwrap();
+
+    assert_eq!("explicit error", &*err.downcast_ref::<RcError>().unwrap().0);
+}
+
+#[test]
+fn local_poison_with_guard_err() {
+    let mut poison = LocalPoison::new(0);
+
+    poison
+        .poison_with(RcError(Rc::from("explicit error")))
+        .unwrap();
+
+    let err = Poison::on_unwind(&mut *poison).unwrap_err().into_error();
+
+    assert_eq!("explicit error", err.source().unwrap().to_string());
+}
+
+#[test]
+fn local_poison_recover_clears_local_error() {
+    let mut poison = LocalPoison::new(0);
+
+    poison
+        .poison_with(RcError(Rc::from("explicit error")))
+        .unwrap();
+
+    drop(Poison::on_unwind(&mut *poison).unwrap_err().recover());
+
+    assert!(!poison.is_poisoned());
+    assert!(poison.local_error().is_none());
+
+    // Poisoning for a different reason doesn't return the old error
+    drop(Poison::unless_recovered(&mut *poison).unwrap());
+
+    assert!(poison.is_poisoned());
+    assert!(poison.local_error().is_none());
+}
+
+#[test]
+fn local_poison_repoisoned_clears_local_error() {
+    let mut poison = LocalPoison::new(0);
+
+    poison
+        .poison_with(RcError(Rc::from("first error")))
+        .unwrap();
+
+    // Poisoning the inner value again while it's still poisoned replaces the reason
+    (*poison).poison_with("second error").unwrap();
+
+    assert!(poison.is_poisoned());
+    assert!(poison.local_error().is_none());
+
+    poison
+        .poison_with(RcError(Rc::from("third error")))
+        .unwrap();
+
+    let err = poison.local_error().unwrap();
+
+    assert_eq!("third error", &*err.downcast_ref::<RcError>().unwrap().0);
+}
+
+#[test]
+fn local_poison_with_frozen() {
+    let mut poison = LocalPoison::new(0);
+
+    poison.freeze().unwrap();
+
+    let err = poison
+        .poison_with(RcError(Rc::from("explicit error")))
+        .unwrap_err();
+
+    assert!(err.is_frozen());
+    assert!(!poison.is_poisoned());
+    assert!(poison.local_error().is_none());
+}
@@ -0,0 +1,144 @@
+use crate::{
+    metrics::{
+        self,
+        clock,
+    },
+    tests::unwind_through_guard,
+    Poison,
+    PoisonGuard,
+};
+use std::{
+    cell::RefCell,
+    panic::Location,
+    sync::{
+        Mutex,
+        MutexGuard,
+    },
+    time::Duration,
+};
+
+// The hold hook is global, so tests that depend on whether it's set can't run at the same time
+static HOOK_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock_hook() -> MutexGuard<'static, ()> {
+    HOOK_LOCK.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+thread_local! {
+    static HELD: RefCell<Vec<(&'static Location<'static>, Duration)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn hook(location: &'static Location<'static>, held_for: Duration) {
+    HELD.with(|held| held.borrow_mut().push((location, held_for)));
+}
+
+fn take_held() -> Vec<(&'static Location<'static>, Duration)> {
+    HELD.with(|held| held.take())
+}
+
+#[test]
+fn metrics_hold_time_reported_on_drop() {
+    let _lock = lock_hook();
+
+    metrics::set_hold_hook(hook);
+    clock::freeze();
+
+    let mut poison = Poison::new(0);
+
+    let acquired_at = Location::caller();
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    clock::advance(Duration::from_millis(5));
+    *guard += 1;
+    clock::advance(Duration::from_millis(2));
+
+    drop(guard);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+
+    clock::advance(Duration::from_millis(3));
+
+    Poison::recover(guard);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    clock::unfreeze();
+    metrics::take_hold_hook();
+
+    let held = take_held();
+
+    assert_eq!(3, held.len());
+
+    assert_eq!(acquired_at.file(), held[0].0.file());
+    assert_eq!(acquired_at.line() + 1, held[0].0.line());
+
+    assert_eq!(Duration::from_millis(7), held[0].1);
+    assert_eq!(Duration::from_millis(3), held[1].1);
+    assert_eq!(Duration::ZERO, held[2].1);
+}
+
+#[test]
+fn metrics_hold_time_not_reported_without_hook() {
+    let _lock = lock_hook();
+
+    let mut poison = Poison::new(0);
+
+    drop(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(take_held().is_empty());
+}
+
+#[test]
+fn metrics_hold_time_reported_on_downgrade() {
+    let _lock = lock_hook();
+
+    metrics::set_hold_hook(hook);
+    clock::freeze();
+
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+
+    clock::advance(Duration::from_millis(4));
+
+    let err = PoisonGuard::downgrade_to_recover(guard).into_error();
+
+    clock::advance(Duration::from_millis(6));
+
+    drop(err);
+
+    clock::unfreeze();
+    metrics::take_hold_hook();
+
+    let held = take_held();
+
+    assert_eq!(1, held.len());
+    assert_eq!(Duration::from_millis(4), held[0].1);
+}
+
+#[test]
+fn metrics_hold_time_not_reported_for_reborrow() {
+    let _lock = lock_hook();
+
+    metrics::set_hold_hook(hook);
+    clock::freeze();
+
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    *PoisonGuard::reborrow(&mut guard) += 1;
+    drop(PoisonGuard::downgrade_to_recover(PoisonGuard::reborrow(&mut guard)).recover());
+
+    clock::advance(Duration::from_millis(3));
+
+    drop(guard);
+
+    clock::unfreeze();
+    metrics::take_hold_hook();
+
+    let held = take_held();
+
+    assert_eq!(1, held.len());
+    assert_eq!(Duration::from_millis(3), held[0].1);
+}
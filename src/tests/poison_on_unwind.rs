@@ -1,7 +1,33 @@
 use crate::{
-    tests::unwind_through_guard,
+    poison::{
+        PoisonError,
+        PoisonGuard,
+    },
+    tests::{
+        some_err,
+        unwind_through_guard,
+        SomeError,
+    },
     Poison,
 };
+use std::{
+    borrow::{
+        Borrow,
+        BorrowMut,
+    },
+    cell::{
+        Cell,
+        RefCell,
+    },
+    marker::PhantomPinned,
+    mem,
+    panic,
+    pin::{
+        pin,
+        Pin,
+    },
+    ptr,
+};
 
 #[test]
 fn guard_on_unwind() {
@@ -18,6 +44,18 @@ fn guard_on_unwind() {
     assert_eq!(1, *poison.get().unwrap());
 }
 
+#[test]
+fn guard_on_unwind_repeated() {
+    let mut poison = Poison::new(0);
+
+    for _ in 0..100 {
+        *Poison::on_unwind(&mut poison).unwrap() += 1;
+    }
+
+    assert_eq!(100, *poison.get().unwrap());
+    assert_eq!(0, poison.poison_generation());
+}
+
 #[test]
 fn guard_on_unwind_poisons_on_panic() {
     let mut poison = Poison::new(0);
@@ -29,6 +67,294 @@ fn guard_on_unwind_poisons_on_panic() {
     assert!(poison.is_poisoned());
 }
 
+#[test]
+fn guard_on_unwind_assume_consistent_panic_does_not_poison() {
+    let mut poison = Poison::new(0);
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+        *guard += 1;
+
+        PoisonGuard::assume_consistent(&mut guard, |v| {
+            assert_eq!(0, *v, "explicit panic");
+        });
+    }));
+
+    // The panic still propagates
+    assert!(result.is_err());
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(1, *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_assume_consistent_reset_on_access() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        PoisonGuard::assume_consistent(&mut guard, |_| panic!("explicit panic"));
+    }));
+
+    // Changes made after the panic was caught aren't known to be consistent
+    *guard += 1;
+
+    unwind_through_guard(guard);
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_on_release_runs_on_drop() {
+    let mut poison = Poison::new(vec![3, 1, 2]);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+    let mut guard = PoisonGuard::on_release(guard, |v| v.sort());
+
+    guard.push(0);
+
+    // The function doesn't run until the guard is dropped
+    assert_eq!(&[3, 1, 2, 0], &**guard);
+
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(&[0, 1, 2, 3], &**poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_on_release_skipped_on_panic() {
+    let released = Cell::new(false);
+    let mut poison = Poison::new(0);
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let guard = Poison::on_unwind(&mut poison).unwrap();
+        let _guard = PoisonGuard::on_release(guard, |_| released.set(true));
+
+        panic!("explicit panic");
+    }));
+
+    assert!(!released.get());
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_on_release_panic_poisons() {
+    let mut poison = Poison::new(0);
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let guard = Poison::on_unwind(&mut poison).unwrap();
+
+        drop(PoisonGuard::on_release(guard, |_| panic!("explicit panic")));
+    }));
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_no_forget_poison_forget() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind_no_forget_poison(&mut poison).unwrap();
+    *guard += 1;
+
+    mem::forget(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(1, *poison.get().unwrap());
+
+    // Ordinary guards can still be acquired afterwards
+    mem::forget(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_no_forget_poison_poisons_on_panic() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind_no_forget_poison(&mut poison).unwrap();
+
+    let acquired_at = PoisonGuard::acquired_at(&guard);
+
+    unwind_through_guard(guard);
+
+    assert!(poison.is_poisoned_by_panic());
+
+    // The value is poisoned at the location the guard was acquired
+    let record = PoisonError::from(poison.get().unwrap_err())
+        .to_record()
+        .unwrap();
+
+    assert_eq!(
+        (acquired_at.file(), acquired_at.line()),
+        (record.file(), record.line())
+    );
+}
+
+#[test]
+fn guard_on_unwind_pinned_poisons_on_panic() {
+    struct SelfRef {
+        value: i32,
+        ptr: *const i32,
+        _pinned: PhantomPinned,
+    }
+
+    impl SelfRef {
+        fn init(self: Pin<&mut Self>) {
+            // SAFETY: The value isn't moved
+            let this = unsafe { self.get_unchecked_mut() };
+            this.ptr = &this.value;
+        }
+
+        fn incr(self: Pin<&mut Self>) {
+            // SAFETY: The value isn't moved
+            let this = unsafe { self.get_unchecked_mut() };
+            this.value += 1;
+        }
+
+        fn get(&self) -> i32 {
+            // SAFETY: The value is pinned, so the pointer is still valid
+            unsafe { *self.ptr }
+        }
+    }
+
+    let mut poison = pin!(Poison::new(SelfRef {
+        value: 0,
+        ptr: ptr::null(),
+        _pinned: PhantomPinned,
+    }));
+
+    let mut guard = Poison::on_unwind_pinned(poison.as_mut()).unwrap();
+
+    guard.as_mut().init();
+    guard.as_mut().incr();
+
+    assert_eq!(1, guard.get());
+    drop(guard);
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let mut guard = Poison::on_unwind_pinned(poison.as_mut()).unwrap();
+
+        guard.as_mut().incr();
+
+        panic!("explicit panic");
+    }));
+
+    assert!(poison.is_poisoned_by_panic());
+    assert_eq!(2, poison.peek().get());
+
+    let err = Poison::on_unwind_pinned(poison.as_mut()).err().unwrap();
+
+    assert!(err.is_unknown_panic());
+}
+
+#[test]
+fn guard_on_unwind_reborrow() {
+    fn incr(mut guard: PoisonGuard<i32>) {
+        *guard += 1;
+    }
+
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+    let acquired_at = PoisonGuard::acquired_at(&guard);
+
+    incr(PoisonGuard::reborrow(&mut guard));
+    Poison::recover(PoisonGuard::reborrow(&mut guard));
+
+    // The outer guard is still protecting the value
+    assert_eq!(1, *guard);
+    assert_eq!(acquired_at, PoisonGuard::acquired_at(&guard));
+
+    mem::forget(guard);
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_reborrow_no_panic_does_not_poison() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    *PoisonGuard::reborrow(&mut guard) += 1;
+
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(1, *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_reborrow_recover_err_keeps_outer_guard() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+    let acquired_at = PoisonGuard::acquired_at(&guard);
+
+    let recover = PoisonGuard::guarded_catch_unwind(PoisonGuard::reborrow(&mut guard), |v| {
+        *v += 1;
+
+        Err::<(), _>(some_err())
+    })
+    .unwrap_err();
+
+    drop(recover.recover());
+
+    // Recovering the reborrow doesn't release the outer guard
+    assert_eq!(acquired_at, PoisonGuard::acquired_at(&guard));
+
+    mem::forget(guard);
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_reborrow_downgrade_recover_keeps_outer_guard() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    drop(PoisonGuard::downgrade_to_recover(PoisonGuard::reborrow(&mut guard)).recover());
+
+    mem::forget(guard);
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_reborrow_downgrade_recover_then_drop() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    drop(
+        PoisonGuard::downgrade_to_recover(PoisonGuard::reborrow(&mut guard))
+            .recover_with(|v| *v = 1),
+    );
+
+    drop(guard);
+
+    assert_eq!(1, *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_reborrow_poisons_on_panic() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    // The panic is caught before it reaches the outer guard
+    unwind_through_guard(PoisonGuard::reborrow(&mut guard));
+
+    drop(guard);
+
+    assert!(poison.is_poisoned_by_panic());
+}
+
 #[test]
 fn guard_on_unwind_recover_on_unwind() {
     let mut poison = Poison::new(0);
@@ -69,3 +395,528 @@ fn guard_on_unwind_recover_with() {
 
     assert_eq!(1, *guard);
 }
+
+#[test]
+fn guard_on_unwind_recover_or_default() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let recover = Poison::on_unwind(&mut poison).unwrap_err();
+
+    let guard = recover.recover_or_default();
+
+    assert!(guard.is_empty());
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+    assert!(poison.get().unwrap().is_empty());
+}
+
+#[test]
+fn guard_on_unwind_recover_replace() {
+    let mut poison = Poison::new(String::from("broken"));
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let recover = Poison::on_unwind(&mut poison).unwrap_err();
+
+    let mut guard = recover.recover_replace(String::from("fresh"));
+
+    assert_eq!("fresh", *guard);
+    guard.push_str(" value");
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!("fresh value", *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_recover_with_factory() {
+    thread_local! {
+        static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[derive(Debug)]
+    struct Resource(i32);
+
+    impl Resource {
+        fn open(id: i32) -> Self {
+            EVENTS.with(|events| events.borrow_mut().push(format!("open {}", id)));
+
+            Resource(id)
+        }
+    }
+
+    impl Drop for Resource {
+        fn drop(&mut self) {
+            EVENTS.with(|events| events.borrow_mut().push(format!("close {}", self.0)));
+        }
+    }
+
+    let mut poison = Poison::new(Resource::open(1));
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let recover = Poison::on_unwind(&mut poison).unwrap_err();
+
+    let guard = recover.recover_with_factory(|| Resource::open(2));
+
+    assert_eq!(2, guard.0);
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+
+    drop(poison);
+
+    assert_eq!(
+        vec!["open 1", "open 2", "close 1", "close 2"],
+        EVENTS.with(|events| events.take())
+    );
+}
+
+#[test]
+fn guard_on_unwind_recover_with_factory_panic() {
+    let mut poison = Poison::new(String::from("broken"));
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let _guard = Poison::on_unwind(&mut poison)
+            .unwrap_err()
+            .recover_with_factory(|| panic!("explicit panic"));
+    }));
+
+    // The old value is left in place and is still poisoned
+    assert!(poison.is_poisoned());
+    assert_eq!("broken", *poison.peek());
+}
+
+#[test]
+fn guard_on_unwind_recover_or_propagate_recovers() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    poison.poison_with(some_err()).unwrap();
+
+    let recover = Poison::on_unwind(&mut poison).unwrap_err();
+
+    let guard = recover
+        .recover_or_propagate(PoisonError::is_recoverable, |v| v.clear())
+        .unwrap();
+
+    assert!(guard.is_empty());
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_recover_or_propagate_propagates() {
+    let mut poison = Poison::new(vec![1, 2, 3]);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let recover = Poison::on_unwind(&mut poison).unwrap_err();
+
+    let err = recover
+        .recover_or_propagate(PoisonError::is_recoverable, |_| {
+            unreachable!("the value shouldn't be recovered")
+        })
+        .unwrap_err();
+
+    assert!(err.is_unknown_panic());
+    assert!(poison.is_poisoned());
+    assert_eq!(&[1, 2, 3], &**poison.peek());
+}
+
+#[test]
+fn guard_on_unwind_downgrade_to_recover() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let recover = PoisonGuard::downgrade_to_recover(guard);
+
+    let guard = recover.recover_with(|i| *i += 1);
+
+    assert_eq!(1, *guard);
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(1, *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_downgrade_to_recover_poisons() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let _ = PoisonGuard::downgrade_to_recover(guard);
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_guarded_catch_unwind() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let (r, guard) = PoisonGuard::guarded_catch_unwind(guard, |i| {
+        *i += 1;
+
+        Ok::<_, SomeError>(*i * 2)
+    })
+    .unwrap();
+
+    assert_eq!(2, r);
+    assert_eq!(1, *guard);
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_guarded_catch_unwind_err() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let recover = PoisonGuard::guarded_catch_unwind(guard, |i| {
+        *i += 1;
+
+        Err::<(), _>(some_err())
+    })
+    .unwrap_err();
+
+    assert!(recover.into_error().downcast_ref::<SomeError>().is_some());
+    assert!(poison.is_poisoned_by_error());
+    assert_eq!(1, *poison.peek());
+}
+
+#[test]
+fn guard_on_unwind_guarded_catch_unwind_panic() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let recover = PoisonGuard::guarded_catch_unwind(guard, |_| -> Result<(), SomeError> {
+        panic!("explicit panic")
+    })
+    .unwrap_err();
+
+    let err = recover.into_error();
+
+    assert!(err.is_captured_panic());
+    assert!(err.to_string().contains("'explicit panic'"));
+
+    // The value can be recovered
+    let guard = Poison::on_unwind(&mut poison).unwrap_err().recover();
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_acquired_at() {
+    let mut poison = Poison::new(0);
+
+    let line = line!() + 1;
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    assert_eq!(file!(), PoisonGuard::acquired_at(&guard).file());
+    assert_eq!(line, PoisonGuard::acquired_at(&guard).line());
+
+    // The location is kept if the value is poisoned while the guard is held
+    let _ = PoisonGuard::try_with(&mut guard, |_| Err::<(), _>(some_err()));
+
+    assert_eq!(line, PoisonGuard::acquired_at(&guard).line());
+}
+
+#[test]
+fn guard_on_unwind_split() {
+    let mut poison = Poison::new((0, String::new()));
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let (mut a, mut b) = PoisonGuard::split(&mut guard);
+
+    *a += 1;
+    b.push('b');
+    *a += 1;
+
+    drop((a, b));
+    drop(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!((2, String::from("b")), *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_split_panic_poisons_pair() {
+    let mut poison = Poison::new((0, String::new()));
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    let (a, mut b) = PoisonGuard::split(&mut guard);
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || {
+        let _a = a;
+
+        panic!("explicit panic");
+    }));
+
+    // The other half can still be used
+    b.push('b');
+
+    drop(b);
+    drop(guard);
+
+    assert!(poison.is_poisoned_by_panic());
+    assert_eq!("b", poison.peek().1);
+}
+
+#[test]
+fn guard_on_unwind_display() {
+    let mut poison = Poison::new(42.5);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+
+    assert_eq!(42.5.to_string(), guard.to_string());
+    assert_eq!(format!("{:>8.2}", 42.5), format!("{:>8.2}", guard));
+}
+
+#[test]
+fn guard_on_unwind_borrow() {
+    fn read(v: impl Borrow<i32>) -> i32 {
+        *v.borrow()
+    }
+
+    fn write(mut v: impl BorrowMut<i32>) {
+        *v.borrow_mut() += 1;
+    }
+
+    let mut poison = Poison::new(0);
+
+    write(Poison::on_unwind(&mut poison).unwrap());
+
+    assert_eq!(1, read(Poison::on_unwind(&mut poison).unwrap()));
+}
+
+#[test]
+fn guard_on_unwind_try_with() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    PoisonGuard::try_with(&mut guard, |i| {
+        *i += 1;
+
+        Ok::<(), SomeError>(())
+    })
+    .unwrap();
+
+    PoisonGuard::try_with(&mut guard, |i| {
+        *i += 1;
+
+        Ok::<(), SomeError>(())
+    })
+    .unwrap();
+
+    drop(guard);
+
+    assert_eq!(2, *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_try_with_err_poisons() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::on_unwind(&mut poison).unwrap();
+
+    assert!(PoisonGuard::try_with(&mut guard, |_| Err::<(), _>(some_err())).is_err());
+
+    drop(guard);
+
+    assert!(poison.is_poisoned());
+
+    // After recovering, later steps succeed
+    let mut guard = Poison::on_unwind(&mut poison).unwrap_err().recover();
+
+    PoisonGuard::try_with(&mut guard, |i| {
+        *i += 1;
+
+        Ok::<(), SomeError>(())
+    })
+    .unwrap();
+
+    drop(guard);
+
+    assert_eq!(1, *poison.get().unwrap());
+}
+
+#[test]
+fn guard_on_unwind_checked_never_poisoned() {
+    let mut poison = Poison::new(0);
+
+    let (guard, last_poisoned) = Poison::on_unwind_checked(&mut poison).unwrap();
+
+    assert_eq!(0, *guard);
+    assert!(last_poisoned.is_none());
+}
+
+#[test]
+fn guard_on_unwind_checked_recovered() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    drop(Poison::on_unwind(&mut poison).unwrap_err().recover());
+
+    let (guard, last_poisoned) = Poison::on_unwind_checked(&mut poison).unwrap();
+
+    assert_eq!(0, *guard);
+    assert!(last_poisoned.unwrap().is_unknown_panic());
+}
+
+#[test]
+fn guard_on_unwind_checked_poisoned() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(Poison::on_unwind_checked(&mut poison).is_err());
+}
+
+#[test]
+fn guard_on_unwind_poisoned_acquire_paths_agree() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let expected = poison.get().err().unwrap().to_string();
+
+    // Every way of acquiring a guard to a poisoned value reports the same error
+    assert_eq!(
+        expected,
+        Poison::on_unwind(&mut poison).err().unwrap().to_string()
+    );
+    assert_eq!(
+        expected,
+        Poison::unless_recovered(&mut poison)
+            .err()
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(
+        expected,
+        Poison::on_unwind_no_forget_poison(&mut poison)
+            .err()
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(
+        expected,
+        Poison::on_unwind_pinned(Pin::new(&mut poison))
+            .err()
+            .unwrap()
+            .to_string()
+    );
+
+    // Recovering still gives back the original value
+    assert_eq!(
+        0,
+        *Poison::unless_recovered(&mut poison)
+            .err()
+            .unwrap()
+            .recover()
+    );
+}
+
+#[test]
+fn guard_on_unwind_clearing_errors_clears_err() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    let _ = Poison::try_recover(Err::<(), SomeError>(some_err()), guard);
+
+    let mut guard = Poison::on_unwind_clearing_errors(&mut poison).unwrap();
+
+    *guard += 1;
+    drop(guard);
+
+    assert_eq!(1, *poison.get().unwrap());
+    assert!(poison.last_poison().unwrap().is_recoverable());
+}
+
+#[test]
+fn guard_on_unwind_downgrade_was_not_guard_drop() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::on_unwind(&mut poison).unwrap();
+    let _ = PoisonGuard::downgrade_to_recover(guard);
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(!err.was_guard_drop());
+}
+
+#[test]
+fn guard_on_unwind_clearing_errors_clears_forgotten_guard() {
+    let mut poison = Poison::new(0);
+
+    mem::forget(Poison::on_unwind(&mut poison).unwrap());
+
+    assert!(PoisonError::from(poison.get().err().unwrap()).was_guard_drop());
+
+    drop(Poison::on_unwind_clearing_errors(&mut poison).unwrap());
+
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+fn guard_on_unwind_clearing_errors_keeps_panic() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let recover = Poison::on_unwind_clearing_errors(&mut poison)
+        .err()
+        .unwrap();
+
+    assert!(!PoisonError::from(recover).is_recoverable());
+    assert!(poison.is_poisoned_by_panic());
+}
+
+#[test]
+fn guard_on_unwind_clearing_errors_poisons_on_panic() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind_clearing_errors(&mut poison).unwrap());
+
+    assert!(poison.is_poisoned_by_panic());
+}
+
+#[test]
+fn guard_on_unwind_with_target() {
+    let mutex = parking_lot::Mutex::new(Poison::new(0));
+
+    let mut guard = Poison::on_unwind(mutex.lock()).unwrap();
+
+    *guard += 1;
+
+    let (locked, contended) = PoisonGuard::with_target(&guard, |lock| {
+        let mutex = parking_lot::MutexGuard::mutex(lock);
+
+        (mutex.is_locked(), mutex.try_lock().is_none())
+    });
+
+    assert!(locked);
+    assert!(contended);
+
+    drop(guard);
+
+    assert!(!mutex.is_locked());
+    assert_eq!(1, *mutex.lock().get().unwrap());
+}
@@ -1,4 +1,5 @@
 use crate::{
+    poison::PoisonError,
     tests::{
         err_through_guard,
         some_err,
@@ -6,6 +7,16 @@ use crate::{
         SomeError,
     },
     Poison,
+    PoisonGuard,
+    ReleasingGuard,
+};
+use std::{
+    cell::Cell,
+    error::Error,
+    io,
+    iter,
+    mem,
+    panic,
 };
 
 #[test]
@@ -29,14 +40,13 @@ fn guard_unless_recovered_try_recover() {
 
     let mut guard = Poison::unless_recovered(&mut poison).unwrap();
 
-    let _ = Poison::try_recover(
-        (|| {
-            *guard += 1;
+    let r = {
+        *guard += 1;
+
+        Ok::<(), SomeError>(())
+    };
 
-            Ok::<(), SomeError>(())
-        })(),
-        guard,
-    );
+    let _ = Poison::try_recover(r, guard);
 
     assert_eq!(1, *poison.get().unwrap());
 }
@@ -47,18 +57,51 @@ fn guard_unless_recovered_poisons_on_try_recover_err() {
 
     let mut guard = Poison::unless_recovered(&mut poison).unwrap();
 
-    let _ = Poison::try_recover(
-        (|| {
-            *guard += 1;
+    let r = {
+        *guard += 1;
 
-            Err::<(), SomeError>(some_err())
-        })(),
-        guard,
-    );
+        Err::<(), SomeError>(some_err())
+    };
+
+    let _ = Poison::try_recover(r, guard);
 
     assert!(poison.is_poisoned());
 }
 
+#[test]
+fn guard_unless_recovered_try_recover_with_code() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+
+    let err = Poison::try_recover_with_code(Err::<(), _>(some_err()), guard, 7).unwrap_err();
+
+    assert_eq!(Some(7), err.code());
+    assert_eq!(Some(7), PoisonError::from(poison.get().unwrap_err()).code());
+
+    // Successful recoveries don't poison
+    let guard = Poison::unless_recovered(&mut poison).unwrap_err().recover();
+
+    Poison::try_recover_with_code(Ok::<(), SomeError>(()), guard, 7).unwrap();
+
+    assert!(!poison.is_poisoned());
+}
+
+#[test]
+fn guard_unless_recovered_err_context() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+
+    let err = Poison::err_context(guard, some_err(), "while compacting segment 5");
+
+    assert!(err.to_string().contains("while compacting segment 5"));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.to_string().contains("while compacting segment 5"));
+}
+
 #[test]
 fn guard_unless_recovered_poisons_on_panic() {
     let mut poison = Poison::new(0);
@@ -81,6 +124,50 @@ fn guard_unless_recovered_poisons_on_drop() {
     assert!(poison.is_poisoned());
 }
 
+#[test]
+fn guard_unless_recovered_drop_was_guard_drop() {
+    let mut poison = Poison::new(0);
+
+    err_through_guard(Poison::unless_recovered(&mut poison).unwrap());
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.was_guard_drop());
+}
+
+#[test]
+fn guard_unless_recovered_panic_was_not_guard_drop() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::unless_recovered(&mut poison).unwrap());
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(!err.was_guard_drop());
+}
+
+#[test]
+fn guard_unless_recovered_downgrade_was_not_guard_drop() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    let err = PoisonError::from(PoisonGuard::downgrade_to_recover(guard));
+
+    assert!(err.is_recoverable());
+    assert!(!err.was_guard_drop());
+}
+
+#[test]
+fn guard_unless_recovered_forget_was_guard_drop() {
+    let mut poison = Poison::new(0);
+
+    mem::forget(Poison::unless_recovered(&mut poison).unwrap());
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+
+    assert!(err.was_guard_drop());
+}
+
 #[test]
 fn guard_unless_recovered_recover_unless_recovered() {
     let mut poison = Poison::new(0);
@@ -121,3 +208,104 @@ fn guard_unless_recovered_recover_with() {
 
     assert_eq!(1, *guard);
 }
+
+#[test]
+fn guard_unless_recovered_try_recover_with_err_keeps_previous_err() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    let _ = Poison::try_recover(Err::<(), _>(io::Error::other("error A")), guard);
+
+    let recover = Poison::unless_recovered(&mut poison).unwrap_err();
+    let recover = recover
+        .try_recover_with(|_| Err(io::Error::other("error B")))
+        .unwrap_err();
+
+    let err = recover.into_error();
+
+    let chain = iter::successors(err.source(), |&err| err.source())
+        .map(|err| err.to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!("error B", chain[0]);
+    assert!(chain.iter().any(|err| err == "error A"));
+}
+
+#[test]
+fn guard_unless_recovered_on_release_recover() {
+    let mut poison = Poison::new(vec![3, 1, 2]);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    let mut guard = PoisonGuard::on_release(guard, |v| v.sort());
+
+    guard.push(0);
+
+    ReleasingGuard::recover(guard);
+
+    assert!(!poison.is_poisoned());
+    assert_eq!(&[0, 1, 2, 3], &**poison.get().unwrap());
+}
+
+#[test]
+fn guard_unless_recovered_on_release_drop_poisons() {
+    let released = Cell::new(false);
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    drop(PoisonGuard::on_release(guard, |_| released.set(true)));
+
+    assert!(!released.get());
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_unless_recovered_on_release_recover_panic_poisons() {
+    let mut poison = Poison::new(0);
+
+    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let guard = Poison::unless_recovered(&mut poison).unwrap();
+
+        ReleasingGuard::recover(PoisonGuard::on_release(guard, |_| panic!("explicit panic")));
+    }));
+
+    assert!(poison.is_poisoned_by_panic());
+}
+
+#[test]
+fn guard_unless_recovered_reborrow_downgrade_recover_keeps_outer_guard() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::unless_recovered(&mut poison).unwrap();
+
+    drop(PoisonGuard::downgrade_to_recover(PoisonGuard::reborrow(&mut guard)).recover());
+
+    // The outer guard was never recovered
+    drop(guard);
+
+    assert!(poison.is_poisoned());
+}
+
+#[test]
+fn guard_unless_recovered_reborrow_recover_err_keeps_outer_guard() {
+    let mut poison = Poison::new(0);
+
+    let mut guard = Poison::unless_recovered(&mut poison).unwrap();
+
+    let recover = PoisonGuard::guarded_catch_unwind(PoisonGuard::reborrow(&mut guard), |_| {
+        Err::<(), _>(some_err())
+    })
+    .unwrap_err();
+
+    Poison::recover(recover.recover_with(|v| *v = 1));
+
+    drop(guard);
+
+    assert!(poison.is_poisoned());
+
+    let mut guard = Poison::unless_recovered(&mut poison).unwrap_err().recover();
+
+    Poison::recover(PoisonGuard::reborrow(&mut guard));
+    Poison::recover(guard);
+
+    assert_eq!(1, *poison.get().unwrap());
+}
@@ -0,0 +1,87 @@
+use crate::{
+    tests::{
+        some_err,
+        SomeError,
+    },
+    Poison,
+    StdPoisonGuard,
+};
+use std::{
+    panic,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    thread,
+};
+
+fn lock(mutex: &Mutex<Poison<i32>>) -> StdPoisonGuard<'_, i32> {
+    Poison::on_unwind_std(mutex.lock()).unwrap()
+}
+
+#[test]
+fn std_mutex_on_unwind_bypasses_std_poison() {
+    let mutex = Arc::new(Mutex::new(Poison::new(0)));
+
+    let handle = {
+        let mutex = mutex.clone();
+
+        thread::spawn(move || {
+            let mut guard = lock(&mutex);
+            *guard += 1;
+
+            panic!("explicit panic");
+        })
+    };
+
+    assert!(handle.join().is_err());
+
+    // The panic poisoned both the `Mutex` and the `Poison<T>`
+    assert!(mutex.is_poisoned());
+    assert!(mutex
+        .lock()
+        .err()
+        .unwrap()
+        .into_inner()
+        .is_poisoned_by_panic());
+
+    let mut guard = Poison::on_unwind_std(mutex.lock()).unwrap_err().recover();
+    *guard += 1;
+    drop(guard);
+
+    // The `Mutex` is still poisoned, but that no longer matters
+    assert!(mutex.is_poisoned());
+    assert_eq!(2, *lock(&mutex));
+}
+
+#[test]
+fn std_mutex_unpoisoned_after_guard_drop() {
+    let mutex = Mutex::new(Poison::new(0));
+
+    *lock(&mutex) += 1;
+
+    assert!(!mutex.is_poisoned());
+    assert_eq!(1, *lock(&mutex));
+}
+
+#[test]
+fn std_mutex_unless_recovered() {
+    let mutex = Mutex::new(Poison::new(0));
+
+    let guard = Poison::unless_recovered_std(mutex.lock()).unwrap();
+    let _ = Poison::try_recover(Err::<(), SomeError>(some_err()), guard);
+
+    let _ = panic::catch_unwind(|| {
+        let _guard = Poison::unless_recovered_std(mutex.lock()).unwrap_err();
+
+        panic!("explicit panic");
+    });
+
+    // The value is still poisoned by the original error
+    let err = Poison::unless_recovered_std(mutex.lock())
+        .unwrap_err()
+        .into_error();
+
+    assert!(err.is_recoverable());
+    assert!(mutex.is_poisoned());
+}
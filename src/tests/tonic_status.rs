@@ -0,0 +1,85 @@
+use crate::{
+    poison::PoisonError,
+    tests::{
+        some_err,
+        unwind_through_guard,
+    },
+    Poison,
+};
+use std::error::Error;
+use tonic::{
+    Code,
+    Status,
+};
+
+#[test]
+fn tonic_status_from_err() {
+    let mut poison = Poison::new(0);
+
+    let guard = Poison::unless_recovered(&mut poison).unwrap();
+    let _ = Poison::err_context(guard, some_err(), "connecting to 10.0.0.1");
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+    let status = Status::from(err.clone());
+
+    assert_eq!(Code::Internal, status.code());
+    assert_eq!("internal state poisoned by an error", status.message());
+
+    // Neither the error context nor the source location are included in the message
+    assert!(!status.message().contains("10.0.0.1"));
+    assert!(!status.message().contains(file!()));
+
+    let source = status
+        .source()
+        .unwrap()
+        .downcast_ref::<PoisonError>()
+        .unwrap();
+
+    assert_eq!(err.to_string(), source.to_string());
+}
+
+#[test]
+fn tonic_status_from_panic() {
+    let poison = Poison::<i32>::new_catch_unwind(|| panic!("secret key: 42"));
+
+    let err = PoisonError::from(poison.get().unwrap_err());
+    assert!(err.is_captured_panic());
+
+    let status = Status::from(err);
+
+    assert_eq!(Code::Internal, status.code());
+    assert_eq!("internal state poisoned by a panic", status.message());
+
+    // The panic is still available to the server through the source
+    let source = status
+        .source()
+        .unwrap()
+        .downcast_ref::<PoisonError>()
+        .unwrap();
+
+    assert_eq!(Some("secret key: 42"), source.message().as_deref());
+}
+
+#[test]
+fn tonic_status_from_unknown_panic() {
+    let mut poison = Poison::new(0);
+
+    unwind_through_guard(Poison::on_unwind(&mut poison).unwrap());
+
+    let status = Status::from(PoisonError::from(poison.get().unwrap_err()));
+
+    assert_eq!(Code::Internal, status.code());
+    assert_eq!("internal state poisoned by a panic", status.message());
+}
+
+#[test]
+fn tonic_status_from_frozen() {
+    let mut poison = Poison::new(0);
+
+    poison.freeze().unwrap();
+
+    let err = PoisonError::from(Poison::on_unwind(&mut poison).err().unwrap());
+    let status = Status::from(err);
+
+    assert_eq!(Code::FailedPrecondition, status.code());
+}